@@ -1,6 +1,6 @@
 use super::core::{
-    af_array, AfError, Array, BinaryOp, Fromf64, HasAfEnum, RealNumber, ReduceByKeyInput, Scanable,
-    HANDLE_ERROR,
+    af_array, af_result, AfError, AfResult, Array, BinaryOp, Fromf64, HasAfEnum, RealNumber,
+    ReduceByKeyInput, Scanable, HANDLE_ERROR,
 };
 
 use libc::{c_double, c_int, c_uint};
@@ -12,6 +12,7 @@ extern "C" {
     fn af_product_nan(out: *mut af_array, input: af_array, dim: c_int, val: c_double) -> c_int;
     fn af_min(out: *mut af_array, input: af_array, dim: c_int) -> c_int;
     fn af_max(out: *mut af_array, input: af_array, dim: c_int) -> c_int;
+    fn af_flat(out: *mut af_array, input: af_array) -> c_int;
     fn af_all_true(out: *mut af_array, input: af_array, dim: c_int) -> c_int;
     fn af_any_true(out: *mut af_array, input: af_array, dim: c_int) -> c_int;
     fn af_count(out: *mut af_array, input: af_array, dim: c_int) -> c_int;
@@ -143,7 +144,7 @@ extern "C" {
 }
 
 macro_rules! dim_reduce_func_def {
-    ($doc_str: expr, $fn_name: ident, $ffi_name: ident, $out_type: ty) => {
+    ($doc_str: expr, $fn_name: ident, $try_name: ident, $ffi_name: ident, $out_type: ty) => {
         #[doc=$doc_str]
         pub fn $fn_name<T>(input: &Array<T>, dim: i32) -> Array<$out_type>
         where
@@ -155,6 +156,22 @@ macro_rules! dim_reduce_func_def {
             HANDLE_ERROR(AfError::from(err_val));
             temp.into()
         }
+
+        #[doc = concat!(
+            "Same as [", stringify!($fn_name), "](./fn.", stringify!($fn_name), ".html), ",
+            "but returns failures as [ArrayFireError](./struct.ArrayFireError.html) instead of ",
+            "invoking the error handler."
+        )]
+        pub fn $try_name<T>(input: &Array<T>, dim: i32) -> AfResult<Array<$out_type>>
+        where
+            T: HasAfEnum,
+            $out_type: HasAfEnum,
+        {
+            let mut temp: af_array = std::ptr::null_mut();
+            let err_val = unsafe { $ffi_name(&mut temp as *mut af_array, input.get(), dim) };
+            af_result(AfError::from(err_val))?;
+            Ok(temp.into())
+        }
     };
 }
 
@@ -185,6 +202,7 @@ dim_reduce_func_def!(
     ```
     ",
     sum,
+    try_sum,
     af_sum,
     T::AggregateOutType
 );
@@ -216,6 +234,7 @@ dim_reduce_func_def!(
     ```
     ",
     product,
+    try_product,
     af_product,
     T::ProductOutType
 );
@@ -247,6 +266,7 @@ dim_reduce_func_def!(
     ```
     ",
     min,
+    try_min,
     af_min,
     T::InType
 );
@@ -278,6 +298,7 @@ dim_reduce_func_def!(
     ```
     ",
     max,
+    try_max,
     af_max,
     T::InType
 );
@@ -309,6 +330,7 @@ dim_reduce_func_def!(
     ```
     ",
     all_true,
+    try_all_true,
     af_all_true,
     bool
 );
@@ -340,6 +362,7 @@ dim_reduce_func_def!(
     ```
     ",
     any_true,
+    try_any_true,
     af_any_true,
     bool
 );
@@ -372,6 +395,7 @@ dim_reduce_func_def!(
     ```
     ",
     count,
+    try_count,
     af_count,
     u32
 );
@@ -403,6 +427,7 @@ dim_reduce_func_def!(
     ```
     ",
     accum,
+    try_accum,
     af_accum,
     T::AggregateOutType
 );
@@ -434,6 +459,7 @@ dim_reduce_func_def!(
     ```
     ",
     diff1,
+    try_diff1,
     af_diff1,
     T::InType
 );
@@ -465,6 +491,7 @@ dim_reduce_func_def!(
     ```
     ",
     diff2,
+    try_diff2,
     af_diff2,
     T::InType
 );
@@ -519,7 +546,7 @@ where
 }
 
 macro_rules! all_reduce_func_def {
-    ($doc_str: expr, $fn_name: ident, $ffi_name: ident, $assoc_type:ident) => {
+    ($doc_str: expr, $fn_name: ident, $try_name: ident, $ffi_name: ident, $assoc_type:ident) => {
         #[doc=$doc_str]
         pub fn $fn_name<T>(
             input: &Array<T>,
@@ -549,6 +576,40 @@ macro_rules! all_reduce_func_def {
                 <<T as HasAfEnum>::$assoc_type as HasAfEnum>::BaseType::fromf64(imag),
             )
         }
+
+        #[doc = concat!(
+            "Same as [", stringify!($fn_name), "](./fn.", stringify!($fn_name), ".html), ",
+            "but returns failures as [ArrayFireError](./struct.ArrayFireError.html) instead of ",
+            "invoking the error handler."
+        )]
+        pub fn $try_name<T>(
+            input: &Array<T>,
+        ) -> AfResult<(
+            <<T as HasAfEnum>::$assoc_type as HasAfEnum>::BaseType,
+            <<T as HasAfEnum>::$assoc_type as HasAfEnum>::BaseType,
+        )>
+        where
+            T: HasAfEnum,
+            <T as HasAfEnum>::$assoc_type: HasAfEnum,
+            <<T as HasAfEnum>::$assoc_type as HasAfEnum>::BaseType: HasAfEnum + Fromf64,
+        {
+            let mut real: f64 = 0.0;
+            let mut imag: f64 = 0.0;
+
+            let err_val = unsafe {
+                $ffi_name(
+                    &mut real as *mut c_double,
+                    &mut imag as *mut c_double,
+                    input.get(),
+                )
+            };
+            af_result(AfError::from(err_val))?;
+
+            Ok((
+                <<T as HasAfEnum>::$assoc_type as HasAfEnum>::BaseType::fromf64(real),
+                <<T as HasAfEnum>::$assoc_type as HasAfEnum>::BaseType::fromf64(imag),
+            ))
+        }
     };
 }
 
@@ -577,6 +638,7 @@ all_reduce_func_def!(
     ```
     ",
     sum_all,
+    try_sum_all,
     af_sum_all,
     AggregateOutType
 );
@@ -607,6 +669,7 @@ all_reduce_func_def!(
     ```
     ",
     product_all,
+    try_product_all,
     af_product_all,
     ProductOutType
 );
@@ -636,6 +699,7 @@ all_reduce_func_def!(
     ```
     ",
     min_all,
+    try_min_all,
     af_min_all,
     InType
 );
@@ -665,12 +729,120 @@ all_reduce_func_def!(
     ```
     ",
     max_all,
+    try_max_all,
     af_max_all,
     InType
 );
 
+macro_rules! typed_all_reduce_func_def {
+    ($doc_str: expr, $fn_name: ident, $ffi_name: ident, $out_type: ty) => {
+        #[doc=$doc_str]
+        pub fn $fn_name<T>(input: &Array<T>) -> AfResult<$out_type>
+        where
+            T: HasAfEnum,
+            $out_type: HasAfEnum,
+        {
+            let mut flat: af_array = std::ptr::null_mut();
+            let err_val = unsafe { af_flat(&mut flat as *mut af_array, input.get()) };
+            af_result(AfError::from(err_val))?;
+            let flat: Array<T> = flat.into();
+
+            let mut temp: af_array = std::ptr::null_mut();
+            let err_val = unsafe { $ffi_name(&mut temp as *mut af_array, flat.get(), 0) };
+            af_result(AfError::from(err_val))?;
+            let reduced: Array<$out_type> = temp.into();
+            reduced.scalar()
+        }
+    };
+}
+
+typed_all_reduce_func_def!(
+    "
+    Sum all values of the Array into a value of the aggregate type
+
+    Unlike [sum_all](./fn.sum_all.html), the result is not passed through `f64`, hence
+    sums of 64 bit integers are exact and complex sums are returned as complex numbers.
+
+    # Parameters
+
+    - `input` is the input Array
+
+    # Return Values
+
+    The sum of all elements. Fails if `input` is empty.
+
+    # Examples
+
+    ```rust
+    use arrayfire::{sum_all_typed, Array, Dim4};
+    let a = Array::new(&[u64::MAX - 1, 1], Dim4::new(&[2, 1, 1, 1]));
+    assert_eq!(sum_all_typed(&a).unwrap(), u64::MAX);
+    ```
+    ",
+    sum_all_typed,
+    af_sum,
+    T::AggregateOutType
+);
+
+typed_all_reduce_func_def!(
+    "
+    Multiply all values of the Array into a value of the product type
+
+    Unlike [product_all](./fn.product_all.html), the result is not passed through `f64`.
+
+    # Parameters
+
+    - `input` is the input Array
+
+    # Return Values
+
+    The product of all elements. Fails if `input` is empty.
+    ",
+    product_all_typed,
+    af_product,
+    T::ProductOutType
+);
+
+typed_all_reduce_func_def!(
+    "
+    Find the minimum among all values of the Array
+
+    Unlike [min_all](./fn.min_all.html), the result has the element type of the Array.
+
+    # Parameters
+
+    - `input` is the input Array
+
+    # Return Values
+
+    The minimum value. Fails if `input` is empty.
+    ",
+    min_all_typed,
+    af_min,
+    T
+);
+
+typed_all_reduce_func_def!(
+    "
+    Find the maximum among all values of the Array
+
+    Unlike [max_all](./fn.max_all.html), the result has the element type of the Array.
+
+    # Parameters
+
+    - `input` is the input Array
+
+    # Return Values
+
+    The maximum value. Fails if `input` is empty.
+    ",
+    max_all_typed,
+    af_max,
+    T
+);
+
 macro_rules! all_reduce_func_def2 {
-    ($doc_str: expr, $fn_name: ident, $ffi_name: ident, $out_type:ty) => {
+    ($doc_str: expr, $fn_name: ident, $try_name: ident, $ffi_name: ident, $out_type:ty) => {
         #[doc=$doc_str]
         pub fn $fn_name<T>(input: &Array<T>) -> ($out_type, $out_type)
         where
@@ -691,6 +863,31 @@ macro_rules! all_reduce_func_def2 {
 
             (<$out_type>::fromf64(real), <$out_type>::fromf64(imag))
         }
+
+        #[doc = concat!(
+            "Same as [", stringify!($fn_name), "](./fn.", stringify!($fn_name), ".html), ",
+            "but returns failures as [ArrayFireError](./struct.ArrayFireError.html) instead of ",
+            "invoking the error handler."
+        )]
+        pub fn $try_name<T>(input: &Array<T>) -> AfResult<($out_type, $out_type)>
+        where
+            T: HasAfEnum,
+            $out_type: HasAfEnum + Fromf64,
+        {
+            let mut real: f64 = 0.0;
+            let mut imag: f64 = 0.0;
+
+            let err_val = unsafe {
+                $ffi_name(
+                    &mut real as *mut c_double,
+                    &mut imag as *mut c_double,
+                    input.get(),
+                )
+            };
+            af_result(AfError::from(err_val))?;
+
+            Ok((<$out_type>::fromf64(real), <$out_type>::fromf64(imag)))
+        }
     };
 }
 
@@ -717,6 +914,7 @@ all_reduce_func_def2!(
     ```
     ",
     all_true_all,
+    try_all_true_all,
     af_all_true_all,
     bool
 );
@@ -744,6 +942,7 @@ all_reduce_func_def2!(
     ```
     ",
     any_true_all,
+    try_any_true_all,
     af_any_true_all,
     bool
 );
@@ -771,6 +970,7 @@ all_reduce_func_def2!(
     ```
     ",
     count_all,
+    try_count_all,
     af_count_all,
     u64
 );
@@ -868,7 +1068,7 @@ where
 }
 
 macro_rules! dim_ireduce_func_def {
-    ($doc_str: expr, $fn_name: ident, $ffi_name: ident, $out_type: ident) => {
+    ($doc_str: expr, $fn_name: ident, $try_name: ident, $ffi_name: ident, $out_type: ident) => {
         #[doc=$doc_str]
         pub fn $fn_name<T>(input: &Array<T>, dim: i32) -> (Array<T::$out_type>, Array<u32>)
         where
@@ -888,6 +1088,30 @@ macro_rules! dim_ireduce_func_def {
             HANDLE_ERROR(AfError::from(err_val));
             (temp.into(), idx.into())
         }
+
+        #[doc = concat!(
+            "Same as [", stringify!($fn_name), "](./fn.", stringify!($fn_name), ".html), ",
+            "but returns failures as [ArrayFireError](./struct.ArrayFireError.html) instead of ",
+            "invoking the error handler."
+        )]
+        pub fn $try_name<T>(input: &Array<T>, dim: i32) -> AfResult<(Array<T::$out_type>, Array<u32>)>
+        where
+            T: HasAfEnum,
+            T::$out_type: HasAfEnum,
+        {
+            let mut temp: af_array = std::ptr::null_mut();
+            let mut idx: af_array = std::ptr::null_mut();
+            let err_val = unsafe {
+                $ffi_name(
+                    &mut temp as *mut af_array,
+                    &mut idx as *mut af_array,
+                    input.get(),
+                    dim,
+                )
+            };
+            af_result(AfError::from(err_val))?;
+            Ok((temp.into(), idx.into()))
+        }
     };
 }
 
@@ -902,7 +1126,7 @@ dim_ireduce_func_def!("
     # Return Values
 
     A tuple of Arrays: Array minimum values and Array containing their index along the reduced dimension.
-    ", imin, af_imin, InType);
+    ", imin, try_imin, af_imin, InType);
 
 dim_ireduce_func_def!("
     Find maximum value along given dimension and their corresponding indices
@@ -915,10 +1139,10 @@ dim_ireduce_func_def!("
     # Return Values
 
     A tuple of Arrays: Array maximum values and Array containing their index along the reduced dimension.
-    ", imax, af_imax, InType);
+    ", imax, try_imax, af_imax, InType);
 
 macro_rules! all_ireduce_func_def {
-    ($doc_str: expr, $fn_name: ident, $ffi_name: ident, $assoc_type:ident) => {
+    ($doc_str: expr, $fn_name: ident, $try_name: ident, $ffi_name: ident, $assoc_type:ident) => {
         #[doc=$doc_str]
         pub fn $fn_name<T>(
             input: &Array<T>,
@@ -952,6 +1176,44 @@ macro_rules! all_ireduce_func_def {
                 temp,
             )
         }
+
+        #[doc = concat!(
+            "Same as [", stringify!($fn_name), "](./fn.", stringify!($fn_name), ".html), ",
+            "but returns failures as [ArrayFireError](./struct.ArrayFireError.html) instead of ",
+            "invoking the error handler."
+        )]
+        pub fn $try_name<T>(
+            input: &Array<T>,
+        ) -> AfResult<(
+            <<T as HasAfEnum>::$assoc_type as HasAfEnum>::BaseType,
+            <<T as HasAfEnum>::$assoc_type as HasAfEnum>::BaseType,
+            u32,
+        )>
+        where
+            T: HasAfEnum,
+            <T as HasAfEnum>::$assoc_type: HasAfEnum,
+            <<T as HasAfEnum>::$assoc_type as HasAfEnum>::BaseType: HasAfEnum + Fromf64,
+        {
+            let mut real: f64 = 0.0;
+            let mut imag: f64 = 0.0;
+            let mut temp: u32 = 0;
+
+            let err_val = unsafe {
+                $ffi_name(
+                    &mut real as *mut c_double,
+                    &mut imag as *mut c_double,
+                    &mut temp as *mut c_uint,
+                    input.get(),
+                )
+            };
+            af_result(AfError::from(err_val))?;
+
+            Ok((
+                <<T as HasAfEnum>::$assoc_type as HasAfEnum>::BaseType::fromf64(real),
+                <<T as HasAfEnum>::$assoc_type as HasAfEnum>::BaseType::fromf64(imag),
+                temp,
+            ))
+        }
     };
 }
 
@@ -972,6 +1234,7 @@ all_ireduce_func_def!(
       * index of minimum element in the third component.
     ",
     imin_all,
+    try_imin_all,
     af_imin_all,
     InType
 );
@@ -992,6 +1255,7 @@ all_ireduce_func_def!(
       - index of maximum element in the third component.
     ",
     imax_all,
+    try_imax_all,
     af_imax_all,
     InType
 );
@@ -1273,7 +1537,7 @@ where
 }
 
 macro_rules! dim_reduce_by_key_func_def {
-    ($brief_str: expr, $ex_str: expr, $fn_name: ident, $ffi_name: ident, $out_type: ty) => {
+    ($brief_str: expr, $ex_str: expr, $fn_name: ident, $try_name: ident, $ffi_name: ident, $out_type: ty) => {
         #[doc=$brief_str]
         /// # Parameters
         ///
@@ -1310,6 +1574,36 @@ macro_rules! dim_reduce_by_key_func_def {
             HANDLE_ERROR(AfError::from(err_val));
             (out_keys.into(), out_vals.into())
         }
+
+        #[doc = concat!(
+            "Same as [", stringify!($fn_name), "](./fn.", stringify!($fn_name), ".html), ",
+            "but returns failures as [ArrayFireError](./struct.ArrayFireError.html) instead of ",
+            "invoking the error handler."
+        )]
+        pub fn $try_name<KeyType, ValueType>(
+            keys: &Array<KeyType>,
+            vals: &Array<ValueType>,
+            dim: i32,
+        ) -> AfResult<(Array<KeyType>, Array<$out_type>)>
+        where
+            KeyType: ReduceByKeyInput,
+            ValueType: HasAfEnum,
+            $out_type: HasAfEnum,
+        {
+            let mut out_keys: af_array = std::ptr::null_mut();
+            let mut out_vals: af_array = std::ptr::null_mut();
+            let err_val = unsafe {
+                $ffi_name(
+                    &mut out_keys as *mut af_array,
+                    &mut out_vals as *mut af_array,
+                    keys.get(),
+                    vals.get(),
+                    dim,
+                )
+            };
+            af_result(AfError::from(err_val))?;
+            Ok((out_keys.into(), out_vals.into()))
+        }
     };
 }
 
@@ -1335,6 +1629,7 @@ print(&out_vals);
 ```
 ",
     all_true_by_key,
+    try_all_true_by_key,
     af_all_true_by_key,
     ValueType::AggregateOutType
 );
@@ -1361,6 +1656,7 @@ print(&out_vals);
 ```
 ",
     any_true_by_key,
+    try_any_true_by_key,
     af_any_true_by_key,
     ValueType::AggregateOutType
 );
@@ -1369,6 +1665,7 @@ dim_reduce_by_key_func_def!(
     "Find total count of elements with similar keys along a given dimension",
     "",
     count_by_key,
+    try_count_by_key,
     af_count_by_key,
     ValueType::AggregateOutType
 );
@@ -1377,6 +1674,7 @@ dim_reduce_by_key_func_def!(
     "Find maximum among values of similar keys along a given dimension",
     "",
     max_by_key,
+    try_max_by_key,
     af_max_by_key,
     ValueType::AggregateOutType
 );
@@ -1385,6 +1683,7 @@ dim_reduce_by_key_func_def!(
     "Find minimum among values of similar keys along a given dimension",
     "",
     min_by_key,
+    try_min_by_key,
     af_min_by_key,
     ValueType::AggregateOutType
 );
@@ -1393,6 +1692,7 @@ dim_reduce_by_key_func_def!(
     "Find product of all values with similar keys along a given dimension",
     "",
     product_by_key,
+    try_product_by_key,
     af_product_by_key,
     ValueType::ProductOutType
 );
@@ -1401,12 +1701,13 @@ dim_reduce_by_key_func_def!(
     "Find sum of all values with similar keys along a given dimension",
     "",
     sum_by_key,
+    try_sum_by_key,
     af_sum_by_key,
     ValueType::AggregateOutType
 );
 
 macro_rules! dim_reduce_by_key_nan_func_def {
-    ($brief_str: expr, $ex_str: expr, $fn_name: ident, $ffi_name: ident, $out_type: ty) => {
+    ($brief_str: expr, $ex_str: expr, $fn_name: ident, $try_name: ident, $ffi_name: ident, $out_type: ty) => {
         #[doc=$brief_str]
         ///
         /// This version of sum by key can replaced all NaN values in the input
@@ -1448,6 +1749,38 @@ macro_rules! dim_reduce_by_key_nan_func_def {
             HANDLE_ERROR(AfError::from(err_val));
             (out_keys.into(), out_vals.into())
         }
+
+        #[doc = concat!(
+            "Same as [", stringify!($fn_name), "](./fn.", stringify!($fn_name), ".html), ",
+            "but returns failures as [ArrayFireError](./struct.ArrayFireError.html) instead of ",
+            "invoking the error handler."
+        )]
+        pub fn $try_name<KeyType, ValueType>(
+            keys: &Array<KeyType>,
+            vals: &Array<ValueType>,
+            dim: i32,
+            replace_value: f64,
+        ) -> AfResult<(Array<KeyType>, Array<$out_type>)>
+        where
+            KeyType: ReduceByKeyInput,
+            ValueType: HasAfEnum,
+            $out_type: HasAfEnum,
+        {
+            let mut out_keys: af_array = std::ptr::null_mut();
+            let mut out_vals: af_array = std::ptr::null_mut();
+            let err_val = unsafe {
+                $ffi_name(
+                    &mut out_keys as *mut af_array,
+                    &mut out_vals as *mut af_array,
+                    keys.get(),
+                    vals.get(),
+                    dim,
+                    replace_value,
+                )
+            };
+            af_result(AfError::from(err_val))?;
+            Ok((out_keys.into(), out_vals.into()))
+        }
     };
 }
 
@@ -1455,6 +1788,7 @@ dim_reduce_by_key_nan_func_def!(
     "Compute sum of all values with similar keys along a given dimension",
     "",
     sum_by_key_nan,
+    try_sum_by_key_nan,
     af_sum_by_key_nan,
     ValueType::AggregateOutType
 );
@@ -1463,6 +1797,7 @@ dim_reduce_by_key_nan_func_def!(
     "Compute product of all values with similar keys along a given dimension",
     "",
     product_by_key_nan,
+    try_product_by_key_nan,
     af_product_by_key_nan,
     ValueType::ProductOutType
 );
@@ -1530,7 +1865,10 @@ where
 #[cfg(test)]
 mod tests {
     use super::super::core::c32;
-    use super::{imax_all, imin_all, product_nan_all, sum_all, sum_nan_all};
+    use super::{
+        imax_all, imin_all, max_all_typed, min_all_typed, product_all_typed, product_nan_all,
+        sum_all, sum_all_typed, sum_nan_all,
+    };
     use crate::core::set_device;
     use crate::randu;
 
@@ -1554,6 +1892,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn typed_all_reduce_api() {
+        set_device(0);
+        let a = crate::Array::new(&[u64::MAX - 3, 1, 2], crate::dim4!(3));
+        assert_eq!(sum_all_typed(&a).unwrap(), u64::MAX);
+        assert_eq!(min_all_typed(&a).unwrap(), 1);
+        assert_eq!(max_all_typed(&a).unwrap(), u64::MAX - 3);
+
+        let b = crate::Array::new(&[c32::new(1.0, 1.0), c32::new(0.0, 2.0)], crate::dim4!(2));
+        assert_eq!(sum_all_typed(&b).unwrap(), c32::new(1.0, 3.0));
+        assert_eq!(product_all_typed(&b).unwrap(), c32::new(-2.0, 2.0));
+
+        let c = randu!(bool; 3, 3);
+        assert!(sum_all_typed(&c).unwrap() <= 9);
+    }
+
     #[test]
     fn all_ireduce_api() {
         set_device(0);
@@ -0,0 +1,642 @@
+use super::algorithm::{max, min, sum};
+use super::blas::{matmul, transpose};
+use super::core::{
+    add, constant, div, eq, exp, gt, log, maxof, moddims, mul, pow, sigmoid, sqrt, sub, tanh, tile,
+    Array, ConstGenerator, ConvGradientType, Dim4, FloatingPoint, Fromf64, HasAfEnum, MatProp,
+    RealFloating,
+};
+use super::ml::{convolve2_gradient_nn, convolve2_nn};
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::rc::Rc;
+
+/// Trait qualifier for types that support automatic differentiation
+///
+/// This trait is implemented for `f32` and `f64`.
+pub trait Differentiable:
+    HasAfEnum<InType = Self, AbsOutType = Self, UnaryOutType = Self, AggregateOutType = Self>
+    + RealFloating
+    + FloatingPoint
+    + ConstGenerator<OutType = Self>
+    + Fromf64
+    + 'static
+{
+}
+
+impl Differentiable for f32 {}
+impl Differentiable for f64 {}
+
+/// Maps gradient of an operation's output to gradients of each of its inputs
+type BackwardFn<T> = Box<dyn Fn(&Array<T>) -> Vec<Array<T>>>;
+
+struct Node<T: HasAfEnum> {
+    value: Array<T>,
+    parents: Vec<Var<T>>,
+    backward: Option<BackwardFn<T>>,
+    grad: RefCell<Option<Array<T>>>,
+    requires_grad: bool,
+}
+
+/// Differentiable variable that records operations performed on it
+///
+/// A `Var` wraps an [Array](./struct.Array.html) and remembers the operations, and their
+/// inputs, that produced it. Calling [backward](./struct.Var.html#method.backward) on the
+/// result of a computation walks this record in reverse and accumulates gradients into
+/// every leaf variable created using [Var::new](./struct.Var.html#method.new).
+///
+/// Elementwise binary operations are carried out in batch mode, so operands can be broadcast
+/// along dimensions of length one. Gradients of broadcast operands are summed back to their
+/// original shape.
+///
+/// Cloning a `Var` is cheap, the clone refers to the same node of the computation graph.
+/// `Var` objects can't be shared across threads.
+///
+/// # Examples
+///
+/// ```rust
+/// use arrayfire::{dim4, randu, Var};
+///
+/// let w = Var::new(randu::<f32>(dim4!(3, 2)));
+/// let x = Var::constant(randu::<f32>(dim4!(2, 4)));
+///
+/// let loss = w.matmul(&x).tanh().sum_all();
+/// loss.backward();
+///
+/// let dw = w.grad().unwrap();
+/// assert_eq!(dw.dims(), dim4!(3, 2));
+/// ```
+pub struct Var<T: HasAfEnum> {
+    node: Rc<Node<T>>,
+}
+
+impl<T: HasAfEnum> Clone for Var<T> {
+    fn clone(&self) -> Self {
+        Self {
+            node: Rc::clone(&self.node),
+        }
+    }
+}
+
+impl<T: HasAfEnum> fmt::Debug for Var<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Var")
+            .field("value", &self.node.value)
+            .field("requires_grad", &self.node.requires_grad)
+            .finish()
+    }
+}
+
+/// Sum `grad` along dimensions where `dims` has length one so that it matches `dims`
+fn reduce_to<T: Differentiable>(grad: Array<T>, dims: Dim4) -> Array<T> {
+    let gdims = grad.dims();
+    let mut result = grad;
+    for axis in 0..4 {
+        if dims[axis] == 1 && gdims[axis] != 1 {
+            result = sum(&result, axis as i32);
+        }
+    }
+    result
+}
+
+/// Expand `grad` reduced along `dim` back to `dims`
+fn expand_along<T: Differentiable>(grad: &Array<T>, dim: i32, dims: Dim4) -> Array<T> {
+    let mut tile_dims = Dim4::new(&[1, 1, 1, 1]);
+    tile_dims[dim as usize] = dims[dim as usize];
+    tile(grad, tile_dims)
+}
+
+fn scalar<T: Differentiable>(value: f64) -> T {
+    T::fromf64(value)
+}
+
+impl<T: Differentiable> Var<T> {
+    fn from_node(
+        value: Array<T>,
+        parents: Vec<Var<T>>,
+        backward: Option<BackwardFn<T>>,
+        requires_grad: bool,
+    ) -> Self {
+        Self {
+            node: Rc::new(Node {
+                value,
+                parents,
+                backward,
+                grad: RefCell::new(None),
+                requires_grad,
+            }),
+        }
+    }
+
+    /// Record the result of an operation on `parents`
+    ///
+    /// The operation is recorded only if any of the parents require gradients. `backward`
+    /// must return one gradient per parent, in the same order as `parents`.
+    pub(crate) fn from_op<F>(value: Array<T>, parents: Vec<Var<T>>, backward: F) -> Self
+    where
+        F: Fn(&Array<T>) -> Vec<Array<T>> + 'static,
+    {
+        if parents.iter().any(|parent| parent.requires_grad()) {
+            Self::from_node(value, parents, Some(Box::new(backward)), true)
+        } else {
+            Self::constant(value)
+        }
+    }
+
+    /// Create a leaf variable whose gradient is to be computed
+    pub fn new(value: Array<T>) -> Self {
+        Self::from_node(value, Vec::new(), None, true)
+    }
+
+    /// Create a variable that is treated as a constant by the gradient computation
+    pub fn constant(value: Array<T>) -> Self {
+        Self::from_node(value, Vec::new(), None, false)
+    }
+
+    /// Returns the Array held by the variable
+    pub fn value(&self) -> &Array<T> {
+        &self.node.value
+    }
+
+    /// Returns the dimensions of the Array held by the variable
+    pub fn dims(&self) -> Dim4 {
+        self.node.value.dims()
+    }
+
+    /// Check if gradients are computed for this variable
+    pub fn requires_grad(&self) -> bool {
+        self.node.requires_grad
+    }
+
+    /// Returns the gradient accumulated by calls to `backward`
+    ///
+    /// Gradients are only stored for leaf variables created using
+    /// [Var::new](./struct.Var.html#method.new). `None` is returned if no gradient has
+    /// reached this variable yet.
+    pub fn grad(&self) -> Option<Array<T>> {
+        self.node.grad.borrow().clone()
+    }
+
+    /// Reset the accumulated gradient
+    pub fn zero_grad(&self) {
+        *self.node.grad.borrow_mut() = None;
+    }
+
+    /// Returns a constant variable holding the same Array, cut off from the computation graph
+    pub fn detach(&self) -> Self {
+        Self::constant(self.node.value.clone())
+    }
+
+    fn id(&self) -> usize {
+        Rc::as_ptr(&self.node) as usize
+    }
+
+    fn accumulate_grad(&self, grad: Array<T>) {
+        let mut slot = self.node.grad.borrow_mut();
+        let total = match slot.take() {
+            Some(prev) => add(&prev, &grad, false),
+            None => grad,
+        };
+        *slot = Some(total);
+    }
+
+    /// Variables of the graph that require gradients, each one after all of its parents
+    fn topological_order(&self) -> Vec<Var<T>> {
+        let mut order = Vec::new();
+        let mut visited = HashSet::new();
+        let mut stack = vec![(self.clone(), false)];
+        while let Some((var, expanded)) = stack.pop() {
+            if expanded {
+                order.push(var);
+                continue;
+            }
+            if !visited.insert(var.id()) {
+                continue;
+            }
+            stack.push((var.clone(), true));
+            for parent in var.node.parents.iter() {
+                if parent.requires_grad() && !visited.contains(&parent.id()) {
+                    stack.push((parent.clone(), false));
+                }
+            }
+        }
+        order
+    }
+
+    /// Compute gradients of this variable with respect to all leaf variables
+    ///
+    /// The gradient of the variable with respect to itself is taken to be an Array of ones,
+    /// which for a single element variable, such as a loss value, is the usual derivative.
+    /// Gradients are added to any gradients accumulated by earlier calls, use
+    /// [zero_grad](./struct.Var.html#method.zero_grad) to reset them.
+    pub fn backward(&self) {
+        let seed = constant(scalar::<T>(1.0), self.dims());
+        self.backward_with(seed);
+    }
+
+    /// Compute gradients using `grad` as the gradient of this variable
+    ///
+    /// `grad` must have the same dimensions as the value of this variable.
+    pub fn backward_with(&self, grad: Array<T>) {
+        if !self.requires_grad() {
+            return;
+        }
+        let mut grads: HashMap<usize, Array<T>> = HashMap::new();
+        grads.insert(self.id(), grad);
+
+        for var in self.topological_order().iter().rev() {
+            let grad = match grads.remove(&var.id()) {
+                Some(grad) => grad,
+                None => continue,
+            };
+            match &var.node.backward {
+                Some(backward) => {
+                    let parent_grads = backward(&grad);
+                    for (parent, pgrad) in var.node.parents.iter().zip(parent_grads) {
+                        if !parent.requires_grad() {
+                            continue;
+                        }
+                        let total = match grads.remove(&parent.id()) {
+                            Some(prev) => add(&prev, &pgrad, false),
+                            None => pgrad,
+                        };
+                        grads.insert(parent.id(), total);
+                    }
+                }
+                None => var.accumulate_grad(grad),
+            }
+        }
+    }
+
+    fn add_var(&self, rhs: &Var<T>) -> Var<T> {
+        let (ldims, rdims) = (self.dims(), rhs.dims());
+        let value = add(self.value(), rhs.value(), true);
+        Self::from_op(value, vec![self.clone(), rhs.clone()], move |g| {
+            vec![reduce_to(g.clone(), ldims), reduce_to(g.clone(), rdims)]
+        })
+    }
+
+    fn sub_var(&self, rhs: &Var<T>) -> Var<T> {
+        let (ldims, rdims) = (self.dims(), rhs.dims());
+        let value = sub(self.value(), rhs.value(), true);
+        Self::from_op(value, vec![self.clone(), rhs.clone()], move |g| {
+            let neg_g = mul(g, &scalar::<T>(-1.0), false);
+            vec![reduce_to(g.clone(), ldims), reduce_to(neg_g, rdims)]
+        })
+    }
+
+    fn mul_var(&self, rhs: &Var<T>) -> Var<T> {
+        let (lhs_val, rhs_val) = (self.value().clone(), rhs.value().clone());
+        let value = mul(&lhs_val, &rhs_val, true);
+        Self::from_op(value, vec![self.clone(), rhs.clone()], move |g| {
+            vec![
+                reduce_to(mul(g, &rhs_val, true), lhs_val.dims()),
+                reduce_to(mul(g, &lhs_val, true), rhs_val.dims()),
+            ]
+        })
+    }
+
+    fn div_var(&self, rhs: &Var<T>) -> Var<T> {
+        let (lhs_val, rhs_val) = (self.value().clone(), rhs.value().clone());
+        let value = div(&lhs_val, &rhs_val, true);
+        let out = value.clone();
+        Self::from_op(value, vec![self.clone(), rhs.clone()], move |g| {
+            let lgrad = div(g, &rhs_val, true);
+            let rgrad = mul(&mul(&lgrad, &out, false), &scalar::<T>(-1.0), false);
+            vec![
+                reduce_to(lgrad, lhs_val.dims()),
+                reduce_to(rgrad, rhs_val.dims()),
+            ]
+        })
+    }
+
+    /// Matrix multiplication of two variables
+    ///
+    /// See [matmul](./fn.matmul.html) for details.
+    pub fn matmul(&self, rhs: &Var<T>) -> Var<T> {
+        let (lhs_val, rhs_val) = (self.value().clone(), rhs.value().clone());
+        let value = matmul(&lhs_val, &rhs_val, MatProp::NONE, MatProp::NONE);
+        Self::from_op(value, vec![self.clone(), rhs.clone()], move |g| {
+            vec![
+                matmul(g, &rhs_val, MatProp::NONE, MatProp::TRANS),
+                matmul(&lhs_val, g, MatProp::TRANS, MatProp::NONE),
+            ]
+        })
+    }
+
+    /// Transpose of a matrix variable
+    pub fn transpose(&self) -> Var<T> {
+        let value = transpose(self.value(), false);
+        Self::from_op(value, vec![self.clone()], |g| vec![transpose(g, false)])
+    }
+
+    /// Change the dimensions of a variable without changing the data
+    ///
+    /// See [moddims](./fn.moddims.html) for details.
+    pub fn moddims(&self, dims: Dim4) -> Var<T> {
+        let in_dims = self.dims();
+        let value = moddims(self.value(), dims);
+        Self::from_op(value, vec![self.clone()], move |g| {
+            vec![moddims(g, in_dims)]
+        })
+    }
+
+    /// Sum elements along a given dimension
+    pub fn sum(&self, dim: i32) -> Var<T> {
+        let in_dims = self.dims();
+        let value = sum(self.value(), dim);
+        Self::from_op(value, vec![self.clone()], move |g| {
+            vec![expand_along(g, dim, in_dims)]
+        })
+    }
+
+    /// Sum all elements, the result is a single element variable
+    pub fn sum_all(&self) -> Var<T> {
+        let in_dims = self.dims();
+        let flat = moddims(self.value(), Dim4::new(&[in_dims.elements(), 1, 1, 1]));
+        let value = sum(&flat, 0);
+        Self::from_op(value, vec![self.clone()], move |g| vec![tile(g, in_dims)])
+    }
+
+    /// Mean of elements along a given dimension
+    pub fn mean(&self, dim: i32) -> Var<T> {
+        let count = self.dims()[dim as usize] as f64;
+        &self.sum(dim) * scalar::<T>(1.0 / count)
+    }
+
+    /// Mean of all elements, the result is a single element variable
+    pub fn mean_all(&self) -> Var<T> {
+        let count = self.dims().elements() as f64;
+        &self.sum_all() * scalar::<T>(1.0 / count)
+    }
+
+    fn extremum(&self, dim: i32, value: Array<T>) -> Var<T> {
+        let input = self.value().clone();
+        let out = value.clone();
+        Self::from_op(value, vec![self.clone()], move |g| {
+            let mask = eq(&input, &out, true).cast::<T>();
+            vec![mul(&mask, &expand_along(g, dim, input.dims()), false)]
+        })
+    }
+
+    /// Maximum of elements along a given dimension
+    ///
+    /// If there are multiple maxima, each of them receives the full gradient.
+    pub fn max(&self, dim: i32) -> Var<T> {
+        self.extremum(dim, max(self.value(), dim))
+    }
+
+    /// Minimum of elements along a given dimension
+    ///
+    /// If there are multiple minima, each of them receives the full gradient.
+    pub fn min(&self, dim: i32) -> Var<T> {
+        self.extremum(dim, min(self.value(), dim))
+    }
+
+    /// Compute e raised to the power of value
+    pub fn exp(&self) -> Var<T> {
+        let value = exp(self.value());
+        let out = value.clone();
+        Self::from_op(value, vec![self.clone()], move |g| {
+            vec![mul(g, &out, false)]
+        })
+    }
+
+    /// Compute the natural logarithm
+    pub fn log(&self) -> Var<T> {
+        let input = self.value().clone();
+        let value = log(&input);
+        Self::from_op(value, vec![self.clone()], move |g| {
+            vec![div(g, &input, false)]
+        })
+    }
+
+    /// Compute the square root
+    pub fn sqrt(&self) -> Var<T> {
+        let value = sqrt(self.value());
+        let out = value.clone();
+        Self::from_op(value, vec![self.clone()], move |g| {
+            let half_g = mul(g, &scalar::<T>(0.5), false);
+            vec![div(&half_g, &out, false)]
+        })
+    }
+
+    /// Raise values to the power of `exponent`
+    pub fn pow(&self, exponent: T) -> Var<T> {
+        let input = self.value().clone();
+        let value = pow(&input, &exponent, false);
+        Self::from_op(value, vec![self.clone()], move |g| {
+            let lowered = pow(&input, &sub(&exponent, &scalar::<T>(1.0), false), false);
+            vec![mul(&mul(g, &lowered, false), &exponent, false)]
+        })
+    }
+
+    /// Compute tanh
+    pub fn tanh(&self) -> Var<T> {
+        let value = tanh(self.value());
+        let out = value.clone();
+        Self::from_op(value, vec![self.clone()], move |g| {
+            let one_minus_sq = sub(&scalar::<T>(1.0), &mul(&out, &out, false), false);
+            vec![mul(g, &one_minus_sq, false)]
+        })
+    }
+
+    /// Compute sigmoid function
+    pub fn sigmoid(&self) -> Var<T> {
+        let value = sigmoid(self.value());
+        let out = value.clone();
+        Self::from_op(value, vec![self.clone()], move |g| {
+            let one_minus = sub(&scalar::<T>(1.0), &out, false);
+            vec![mul(g, &mul(&out, &one_minus, false), false)]
+        })
+    }
+
+    /// Compute rectified linear unit, i.e. maximum of value and zero
+    pub fn relu(&self) -> Var<T> {
+        let input = self.value().clone();
+        let zeros = constant(scalar::<T>(0.0), input.dims());
+        let value = maxof(&input, &zeros, false);
+        Self::from_op(value, vec![self.clone()], move |g| {
+            let mask = gt(&input, &scalar::<T>(0.0), false).cast::<T>();
+            vec![mul(g, &mask, false)]
+        })
+    }
+
+    /// Convolution of a signal variable with a filter variable
+    ///
+    /// See [convolve2_nn](./fn.convolve2_nn.html) for details on the parameters.
+    pub fn convolve2_nn(
+        &self,
+        filter: &Var<T>,
+        strides: Dim4,
+        padding: Dim4,
+        dilation: Dim4,
+    ) -> Var<T> {
+        let (signal_val, filter_val) = (self.value().clone(), filter.value().clone());
+        let value = convolve2_nn(&signal_val, &filter_val, strides, padding, dilation);
+        let out = value.clone();
+        Self::from_op(value, vec![self.clone(), filter.clone()], move |g| {
+            let grad = |grad_type| {
+                convolve2_gradient_nn(
+                    g,
+                    &signal_val,
+                    &filter_val,
+                    &out,
+                    strides,
+                    padding,
+                    dilation,
+                    grad_type,
+                )
+            };
+            vec![grad(ConvGradientType::DATA), grad(ConvGradientType::FILTER)]
+        })
+    }
+}
+
+macro_rules! var_binary_op {
+    ($op_name:ident, $fn_name:ident, $delegate:ident) => {
+        impl<'a, 'b, T: Differentiable> $op_name<&'a Var<T>> for &'b Var<T> {
+            type Output = Var<T>;
+
+            fn $fn_name(self, rhs: &'a Var<T>) -> Self::Output {
+                self.$delegate(rhs)
+            }
+        }
+
+        impl<T: Differentiable> $op_name<Var<T>> for Var<T> {
+            type Output = Var<T>;
+
+            fn $fn_name(self, rhs: Var<T>) -> Self::Output {
+                self.$delegate(&rhs)
+            }
+        }
+
+        impl<'a, T: Differentiable> $op_name<&'a Var<T>> for Var<T> {
+            type Output = Var<T>;
+
+            fn $fn_name(self, rhs: &'a Var<T>) -> Self::Output {
+                self.$delegate(rhs)
+            }
+        }
+
+        impl<'a, T: Differentiable> $op_name<Var<T>> for &'a Var<T> {
+            type Output = Var<T>;
+
+            fn $fn_name(self, rhs: Var<T>) -> Self::Output {
+                self.$delegate(&rhs)
+            }
+        }
+
+        impl<'a, T: Differentiable> $op_name<T> for &'a Var<T> {
+            type Output = Var<T>;
+
+            fn $fn_name(self, rhs: T) -> Self::Output {
+                let rhs = Var::constant(constant(rhs, Dim4::new(&[1, 1, 1, 1])));
+                self.$delegate(&rhs)
+            }
+        }
+
+        impl<T: Differentiable> $op_name<T> for Var<T> {
+            type Output = Var<T>;
+
+            fn $fn_name(self, rhs: T) -> Self::Output {
+                (&self).$fn_name(rhs)
+            }
+        }
+    };
+}
+
+var_binary_op!(Add, add, add_var);
+var_binary_op!(Sub, sub, sub_var);
+var_binary_op!(Mul, mul, mul_var);
+var_binary_op!(Div, div, div_var);
+
+impl<T: Differentiable> Neg for &Var<T> {
+    type Output = Var<T>;
+
+    fn neg(self) -> Self::Output {
+        self * scalar::<T>(-1.0)
+    }
+}
+
+impl<T: Differentiable> Neg for Var<T> {
+    type Output = Var<T>;
+
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Var;
+    use crate::algorithm::{max_all, sum_all};
+    use crate::core::{abs, constant, set_device, sub, Array};
+    use crate::dim4;
+    use crate::randu;
+
+    fn max_abs_diff(a: &Array<f32>, b: &Array<f32>) -> f32 {
+        max_all(&abs(&sub(a, b, false))).0
+    }
+
+    #[test]
+    fn elementwise_gradients() {
+        set_device(0);
+        let a = Var::new(randu!(3, 3) + 1.0f32);
+        let b = Var::new(randu!(3, 3) + 1.0f32);
+
+        // d/da sum(a * b + a / b) = b + 1 / b
+        let loss = (&a * &b + &a / &b).sum_all();
+        loss.backward();
+
+        let expected_a = b.value() + &(1.0f32 / b.value());
+        assert!(max_abs_diff(&a.grad().unwrap(), &expected_a) < 1e-4);
+
+        // d/db sum(a * b + a / b) = a - a / b^2
+        let b_sq = b.value() * b.value();
+        let expected_b = a.value() - &(a.value() / &b_sq);
+        assert!(max_abs_diff(&b.grad().unwrap(), &expected_b) < 1e-4);
+    }
+
+    #[test]
+    fn broadcast_gradient_reduced_to_operand_shape() {
+        set_device(0);
+        let x = Var::constant(randu!(4, 3));
+        let bias = Var::new(constant(0.5f32, dim4!(4, 1)));
+
+        (&x + &bias).sum_all().backward();
+
+        let grad = bias.grad().unwrap();
+        assert_eq!(grad.dims(), dim4!(4, 1));
+        assert!(max_abs_diff(&grad, &constant(3.0f32, dim4!(4, 1))) < 1e-5);
+    }
+
+    #[test]
+    fn matmul_gradient() {
+        set_device(0);
+        let w = Var::new(randu!(2, 3));
+        let x = Var::constant(randu!(3, 4));
+
+        w.matmul(&x).sum_all().backward();
+
+        // d/dw sum(w x) = ones(2, 4) x^T, i.e. every row holds row sums of x
+        let row_sums = crate::algorithm::sum(x.value(), 1);
+        let expected = crate::blas::transpose(&crate::core::tile(&row_sums, dim4!(1, 2)), false);
+        assert!(max_abs_diff(&w.grad().unwrap(), &expected) < 1e-4);
+    }
+
+    #[test]
+    fn gradients_accumulate_until_reset() {
+        set_device(0);
+        let a = Var::new(randu!(2, 2));
+        let loss = (&a * 2.0f32).sum_all();
+        loss.backward();
+        loss.backward();
+        assert_eq!(sum_all(&a.grad().unwrap()).0, 16.0);
+
+        a.zero_grad();
+        assert!(a.grad().is_none());
+    }
+}
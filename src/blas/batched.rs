@@ -0,0 +1,289 @@
+use super::super::core::{
+    moddims, tile, AfError, AfResult, Array, ArrayFireError, DType, Dim4, FloatingPoint,
+    HalfAccumulation, HasAfEnum, MatProp,
+};
+use super::matmul;
+
+/// Dimensions of the matrix `dims` after applying `prop`
+fn matrix_dims(dims: Dim4, prop: MatProp) -> (u64, u64) {
+    match prop {
+        MatProp::TRANS | MatProp::CTRANS => (dims[1], dims[0]),
+        _ => (dims[0], dims[1]),
+    }
+}
+
+/// Broadcast batch dimensions of `lhs` and `rhs`, each pair has to match or one of them be 1
+fn batch_dims(lhs: Dim4, rhs: Dim4) -> AfResult<[u64; 2]> {
+    let mut batch = [1u64; 2];
+    for (i, dim) in batch.iter_mut().enumerate() {
+        let (l, r) = (lhs[i + 2], rhs[i + 2]);
+        *dim = if l == r || r == 1 {
+            l
+        } else if l == 1 {
+            r
+        } else {
+            return Err(ArrayFireError::with_message(
+                AfError::ERR_SIZE,
+                format!(
+                    "batch dimensions of {} and {} can not be broadcast",
+                    lhs, rhs
+                ),
+            ));
+        };
+    }
+    Ok(batch)
+}
+
+/// Tile the batch dimensions of `input` up to `batch`
+fn broadcast_batch<T: HasAfEnum>(input: &Array<T>, batch: [u64; 2]) -> Array<T> {
+    let dims = input.dims();
+    if dims[2] == batch[0] && dims[3] == batch[1] {
+        input.clone()
+    } else {
+        tile(
+            input,
+            Dim4::new(&[1, 1, batch[0] / dims[2], batch[1] / dims[3]]),
+        )
+    }
+}
+
+fn matmul_broadcast<T>(
+    lhs: &Array<T>,
+    rhs: &Array<T>,
+    optlhs: MatProp,
+    optrhs: MatProp,
+) -> AfResult<Array<T>>
+where
+    T: HasAfEnum + FloatingPoint,
+{
+    let (ldims, rdims) = (lhs.dims(), rhs.dims());
+    let (m, k) = matrix_dims(ldims, optlhs);
+    let (rk, n) = matrix_dims(rdims, optrhs);
+    if k != rk {
+        return Err(ArrayFireError::with_message(
+            AfError::ERR_SIZE,
+            format!(
+                "inner dimensions of lhs {} and rhs {} do not match",
+                ldims, rdims
+            ),
+        ));
+    }
+    let batch = batch_dims(ldims, rdims)?;
+    let out_dims = Dim4::new(&[m, n, batch[0], batch[1]]);
+    if out_dims.elements() == 0 {
+        return Ok(Array::new_empty(out_dims));
+    }
+
+    if ldims[2] == rdims[2] && ldims[3] == rdims[3] {
+        return Ok(matmul(lhs, rhs, optlhs, optrhs));
+    }
+    if ldims[2] * ldims[3] == 1 && optrhs == MatProp::NONE {
+        // A single lhs matrix applies to all columns of the rhs batch at once
+        let folded = moddims(rhs, Dim4::new(&[k, n * batch[0] * batch[1], 1, 1]));
+        return Ok(moddims(
+            &matmul(lhs, &folded, optlhs, MatProp::NONE),
+            out_dims,
+        ));
+    }
+    Ok(matmul(
+        &broadcast_batch(lhs, batch),
+        &broadcast_batch(rhs, batch),
+        optlhs,
+        optrhs,
+    ))
+}
+
+/// Batched matrix multiplication with broadcasting of batch dimensions
+///
+/// The first two dimensions of `lhs` and `rhs` hold the matrices, the third and fourth
+/// dimensions index the batch. A batch dimension of size 1 is broadcast against the
+/// corresponding dimension of the other operand, so a single weight matrix can be multiplied
+/// with a batch of inputs, or a `[m, k, 1, c]` batch with a `[k, n, b, 1]` batch to give a
+/// `[m, n, b, c]` result. Unlike [matmul](./fn.matmul.html), the batch dimensions don't have to
+/// match exactly.
+///
+/// # Parameters
+///
+/// - `lhs` is the Array on left hand side
+/// - `rhs` is the Array on right hand side
+/// - `optlhs` - Transpose left hand side before the function is performed, uses one of the values of [MatProp](./enum.MatProp.html)
+/// - `optrhs` - Transpose right hand side before the function is performed, uses one of the values of [MatProp](./enum.MatProp.html)
+/// - `accumulation` selects the precision used to multiply f16 Arrays, it is ignored for other
+///   types. See [HalfAccumulation](./enum.HalfAccumulation.html).
+///
+/// # Return Values
+///
+/// The batch of products. Fails with `ERR_SIZE` if the inner dimensions don't match or if the
+/// batch dimensions can't be broadcast.
+///
+/// # Examples
+///
+/// ```rust
+/// use arrayfire::{batched_matmul, randu, Dim4, HalfAccumulation, MatProp};
+/// let weights = randu::<f32>(Dim4::new(&[8, 4, 1, 1]));
+/// let inputs = randu::<f32>(Dim4::new(&[4, 1, 16, 1]));
+/// let outputs = batched_matmul(
+///     &weights,
+///     &inputs,
+///     MatProp::NONE,
+///     MatProp::NONE,
+///     HalfAccumulation::DEFAULT,
+/// )
+/// .unwrap();
+/// assert_eq!(outputs.dims(), Dim4::new(&[8, 1, 16, 1]));
+/// ```
+pub fn batched_matmul<T>(
+    lhs: &Array<T>,
+    rhs: &Array<T>,
+    optlhs: MatProp,
+    optrhs: MatProp,
+    accumulation: HalfAccumulation,
+) -> AfResult<Array<T>>
+where
+    T: HasAfEnum + FloatingPoint,
+{
+    if accumulation == HalfAccumulation::F32 && T::get_af_dtype() == DType::F16 {
+        let product = matmul_broadcast(&lhs.cast::<f32>(), &rhs.cast::<f32>(), optlhs, optrhs)?;
+        Ok(product.cast::<T>())
+    } else {
+        matmul_broadcast(lhs, rhs, optlhs, optrhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::batched_matmul;
+    use crate::core::{
+        set_backend, set_device, AfError, Array, Backend, Dim4, HalfAccumulation, MatProp,
+    };
+    use half::f16;
+
+    fn to_vec<T: crate::core::HasAfEnum + Default + Clone>(array: &Array<T>) -> Vec<T> {
+        let mut data = vec![T::default(); array.elements()];
+        array.host(&mut data);
+        data
+    }
+
+    /// Multiplies every pair of broadcast matrices using explicit loops
+    fn reference(lhs: &[f32], ldims: [u64; 4], rhs: &[f32], rdims: [u64; 4]) -> Vec<f32> {
+        let (m, k, n) = (ldims[0], ldims[1], rdims[1]);
+        let b2 = ldims[2].max(rdims[2]);
+        let b3 = ldims[3].max(rdims[3]);
+        let mut out = vec![0.0f32; (m * n * b2 * b3) as usize];
+        for c in 0..b3 {
+            for b in 0..b2 {
+                let loff = ((b % ldims[2]) + (c % ldims[3]) * ldims[2]) * m * k;
+                let roff = ((b % rdims[2]) + (c % rdims[3]) * rdims[2]) * k * n;
+                let ooff = (b + c * b2) * m * n;
+                for j in 0..n {
+                    for i in 0..m {
+                        let mut acc = 0.0f32;
+                        for p in 0..k {
+                            acc +=
+                                lhs[(loff + i + p * m) as usize] * rhs[(roff + p + j * k) as usize];
+                        }
+                        out[(ooff + i + j * m) as usize] = acc;
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Results are compared exactly against the host reference, hence run on the CPU backend
+    fn use_cpu() {
+        set_backend(Backend::CPU);
+        set_device(0);
+    }
+
+    fn iota(dims: [u64; 4], offset: f32) -> Vec<f32> {
+        (0..dims.iter().product::<u64>())
+            .map(|v| (v % 7) as f32 + offset)
+            .collect()
+    }
+
+    #[test]
+    fn batched_matmul_broadcasts_batch_dims() {
+        use_cpu();
+        let cases: [([u64; 4], [u64; 4]); 4] = [
+            ([3, 2, 1, 1], [2, 4, 5, 1]),
+            ([3, 2, 5, 1], [2, 4, 1, 1]),
+            ([3, 2, 1, 2], [2, 4, 3, 1]),
+            ([3, 2, 3, 2], [2, 4, 3, 2]),
+        ];
+        for (ldims, rdims) in cases.iter() {
+            let lhs = iota(*ldims, -2.0);
+            let rhs = iota(*rdims, 1.0);
+            let a = Array::new(&lhs, Dim4::new(ldims));
+            let b = Array::new(&rhs, Dim4::new(rdims));
+            let c = batched_matmul(
+                &a,
+                &b,
+                MatProp::NONE,
+                MatProp::NONE,
+                HalfAccumulation::DEFAULT,
+            )
+            .unwrap();
+            assert_eq!(
+                c.dims(),
+                Dim4::new(&[3, 4, ldims[2].max(rdims[2]), ldims[3].max(rdims[3])])
+            );
+            assert_eq!(to_vec(&c), reference(&lhs, *ldims, &rhs, *rdims));
+        }
+
+        // Transposed lhs against a broadcast rhs batch
+        let lhs = iota([3, 2, 1, 1], 0.0);
+        let rhs = iota([3, 4, 2, 1], 1.0);
+        let a = Array::new(&lhs, crate::dim4!(3, 2));
+        let b = Array::new(&rhs, crate::dim4!(3, 4, 2));
+        let c = batched_matmul(
+            &a,
+            &b,
+            MatProp::TRANS,
+            MatProp::NONE,
+            HalfAccumulation::DEFAULT,
+        )
+        .unwrap();
+        let at: Vec<f32> = (0..6).map(|v| lhs[(v / 2) + (v % 2) * 3]).collect();
+        assert_eq!(to_vec(&c), reference(&at, [2, 3, 1, 1], &rhs, [3, 4, 2, 1]));
+
+        let err = batched_matmul(
+            &b,
+            &Array::new(&iota([4, 2, 3, 1], 0.0), crate::dim4!(4, 2, 3)),
+            MatProp::NONE,
+            MatProp::NONE,
+            HalfAccumulation::DEFAULT,
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), AfError::ERR_SIZE);
+        let err = batched_matmul(
+            &a,
+            &b,
+            MatProp::NONE,
+            MatProp::NONE,
+            HalfAccumulation::DEFAULT,
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), AfError::ERR_SIZE);
+    }
+
+    #[test]
+    fn batched_matmul_half_accumulation() {
+        use_cpu();
+        let (ldims, rdims) = ([2, 3, 1, 1], [3, 2, 4, 1]);
+        let lhs = iota(ldims, 0.0);
+        let rhs = iota(rdims, -1.0);
+        let expected: Vec<f16> = reference(&lhs, ldims, &rhs, rdims)
+            .into_iter()
+            .map(f16::from_f32)
+            .collect();
+        let to_half = |v: &[f32]| v.iter().copied().map(f16::from_f32).collect::<Vec<_>>();
+        let a = Array::new(&to_half(&lhs), Dim4::new(&ldims));
+        let b = Array::new(&to_half(&rhs), Dim4::new(&rdims));
+        for accumulation in [HalfAccumulation::DEFAULT, HalfAccumulation::F32].iter() {
+            let c = batched_matmul(&a, &b, MatProp::NONE, MatProp::NONE, *accumulation).unwrap();
+            assert_eq!(c.dims(), crate::dim4!(2, 2, 4));
+            assert_eq!(to_vec(&c), expected);
+        }
+    }
+}
@@ -0,0 +1,588 @@
+use super::super::algorithm::sum;
+use super::super::core::{
+    moddims, reorder_v2, AfError, AfResult, Array, ArrayFireError, Dim4, FloatingPoint, HasAfEnum,
+    MatProp,
+};
+use super::matmul;
+
+use std::collections::HashMap;
+
+fn arg_error(message: String) -> ArrayFireError {
+    ArrayFireError::with_message(AfError::ERR_ARG, message)
+}
+
+fn size_error(message: String) -> ArrayFireError {
+    ArrayFireError::with_message(AfError::ERR_SIZE, message)
+}
+
+fn fold_error(labels: &[char]) -> ArrayFireError {
+    ArrayFireError::with_message(
+        AfError::ERR_NOT_SUPPORTED,
+        format!(
+            "intermediate result with subscripts '{}' has more than 4 dimensions",
+            labels.iter().collect::<String>()
+        ),
+    )
+}
+
+/// Operand of a contraction, dimension `i` of `array` corresponds to `labels[i]`
+struct Term<T: HasAfEnum> {
+    array: Array<T>,
+    labels: Vec<char>,
+}
+
+/// Contraction of Arrays whose dimensions are identified by subscript labels
+struct Contraction {
+    sizes: HashMap<char, u64>,
+}
+
+impl Contraction {
+    fn dims(&self, labels: &[char]) -> AfResult<Dim4> {
+        if labels.len() > 4 {
+            return Err(fold_error(labels));
+        }
+        let mut dims = [1u64; 4];
+        for (dim, label) in dims.iter_mut().zip(labels) {
+            *dim = self.sizes[label];
+        }
+        Ok(Dim4::new(&dims))
+    }
+
+    fn elements(&self, labels: &[char]) -> u64 {
+        labels.iter().map(|label| self.sizes[label]).product()
+    }
+
+    /// Sums `term` along the dimension of `label` and drops the label
+    fn reduce<T>(&self, term: Term<T>, label: char) -> AfResult<Term<T>>
+    where
+        T: HasAfEnum<AggregateOutType = T>,
+    {
+        let dim = term.labels.iter().position(|&l| l == label).unwrap();
+        let summed = sum(&term.array, dim as i32);
+        let mut labels = term.labels;
+        labels.remove(dim);
+        Ok(Term {
+            array: moddims(&summed, self.dims(&labels)?),
+            labels,
+        })
+    }
+
+    /// Reorders the dimensions of `term` to follow `labels`
+    fn permute<T: HasAfEnum>(&self, term: Term<T>, labels: &[char]) -> Term<T> {
+        if term.labels == labels {
+            return term;
+        }
+        let mut axes = [0u64, 1, 2, 3];
+        for (axis, label) in axes.iter_mut().zip(labels) {
+            *axis = term.labels.iter().position(|l| l == label).unwrap() as u64;
+        }
+        Term {
+            array: reorder_v2(&term.array, axes[0], axes[1], Some(vec![axes[2], axes[3]])),
+            labels: labels.to_vec(),
+        }
+    }
+
+    /// Folds `term`, whose labels are `first` followed by `second` and `batch`, into a
+    /// batch of matrices
+    fn fold<T: HasAfEnum>(
+        &self,
+        term: &Term<T>,
+        first: &[char],
+        second: &[char],
+        batch: &[char],
+    ) -> Array<T> {
+        let dims = Dim4::new(&[
+            self.elements(first),
+            self.elements(second),
+            self.elements(batch),
+            1,
+        ]);
+        if term.array.dims() == dims {
+            term.array.clone()
+        } else {
+            moddims(&term.array, dims)
+        }
+    }
+
+    /// Lays out `term` as a batch of matrices with `rows` x `cols` or their transpose
+    fn as_matrix<T: HasAfEnum>(
+        &self,
+        term: Term<T>,
+        rows: &[char],
+        cols: &[char],
+        batch: &[char],
+    ) -> (Array<T>, MatProp) {
+        let concat = |a: &[char], b: &[char]| -> Vec<char> {
+            a.iter().chain(b).chain(batch).copied().collect()
+        };
+        if term.labels == concat(cols, rows) && !rows.is_empty() && !cols.is_empty() {
+            (self.fold(&term, cols, rows, batch), MatProp::TRANS)
+        } else {
+            let term = self.permute(term, &concat(rows, cols));
+            (self.fold(&term, rows, cols, batch), MatProp::NONE)
+        }
+    }
+
+    /// Contracts `lhs` and `rhs`, keeping the labels for which `keep` returns true
+    fn contract<T, F>(&self, lhs: Term<T>, rhs: Term<T>, keep: F) -> AfResult<Term<T>>
+    where
+        T: HasAfEnum<AggregateOutType = T> + FloatingPoint,
+        F: Fn(char) -> bool,
+    {
+        let mut lhs = lhs;
+        let mut rhs = rhs;
+        for label in lhs.labels.clone() {
+            if !keep(label) && !rhs.labels.contains(&label) {
+                lhs = self.reduce(lhs, label)?;
+            }
+        }
+        for label in rhs.labels.clone() {
+            if !keep(label) && !lhs.labels.contains(&label) {
+                rhs = self.reduce(rhs, label)?;
+            }
+        }
+
+        let shared = |label: &&char| rhs.labels.contains(label);
+        let batch: Vec<char> = lhs
+            .labels
+            .iter()
+            .filter(shared)
+            .filter(|&&l| keep(l))
+            .copied()
+            .collect();
+        let inner: Vec<char> = lhs
+            .labels
+            .iter()
+            .filter(shared)
+            .filter(|&&l| !keep(l))
+            .copied()
+            .collect();
+        let left: Vec<char> = lhs
+            .labels
+            .iter()
+            .filter(|l| !rhs.labels.contains(l))
+            .copied()
+            .collect();
+        let right: Vec<char> = rhs
+            .labels
+            .iter()
+            .filter(|l| !lhs.labels.contains(l))
+            .copied()
+            .collect();
+
+        let labels: Vec<char> = left.iter().chain(&right).chain(&batch).copied().collect();
+        let dims = self.dims(&labels)?;
+
+        let (a, opa) = self.as_matrix(lhs, &left, &inner, &batch);
+        let (b, opb) = self.as_matrix(rhs, &inner, &right, &batch);
+        let product = matmul(&a, &b, opa, opb);
+        Ok(Term {
+            array: moddims(&product, dims),
+            labels,
+        })
+    }
+
+    /// Contracts all `terms` into a single Array with dimensions `output`
+    fn evaluate<T>(&self, mut terms: Vec<Term<T>>, output: &[char]) -> AfResult<Array<T>>
+    where
+        T: HasAfEnum<AggregateOutType = T> + FloatingPoint,
+    {
+        while terms.len() > 1 {
+            // Pick the pair with the smallest intermediate result
+            let mut best: Option<(u64, usize, usize)> = None;
+            for i in 0..terms.len() {
+                for j in i + 1..terms.len() {
+                    let kept = self.kept_labels(&terms, i, j, output);
+                    let size = self.elements(&kept);
+                    match best {
+                        Some((s, _, _)) if s <= size => {}
+                        _ => best = Some((size, i, j)),
+                    }
+                }
+            }
+            let (_, i, j) = best.unwrap();
+            let kept = self.kept_labels(&terms, i, j, output);
+            let rhs = terms.remove(j);
+            let lhs = terms.remove(i);
+            let result = self.contract(lhs, rhs, |label| kept.contains(&label))?;
+            terms.push(result);
+        }
+
+        let mut term = terms.pop().unwrap();
+        for label in term.labels.clone() {
+            if !output.contains(&label) {
+                term = self.reduce(term, label)?;
+            }
+        }
+        Ok(self.permute(term, output).array)
+    }
+
+    /// Labels of terms `i` and `j` needed by the output or any other term
+    fn kept_labels<T: HasAfEnum>(
+        &self,
+        terms: &[Term<T>],
+        i: usize,
+        j: usize,
+        output: &[char],
+    ) -> Vec<char> {
+        let mut kept: Vec<char> = Vec::new();
+        for &label in terms[i].labels.iter().chain(&terms[j].labels) {
+            let needed = output.contains(&label)
+                || terms
+                    .iter()
+                    .enumerate()
+                    .any(|(k, t)| k != i && k != j && t.labels.contains(&label));
+            if needed && !kept.contains(&label) {
+                kept.push(label);
+            }
+        }
+        kept
+    }
+}
+
+/// Builds the contraction after checking `labels` against the dimensions of `operands`
+fn prepare<T: HasAfEnum>(
+    labels: &[Vec<char>],
+    operands: &[&Array<T>],
+) -> AfResult<(Contraction, Vec<Term<T>>)> {
+    if labels.len() != operands.len() {
+        return Err(arg_error(format!(
+            "{} subscripts given for {} operands",
+            labels.len(),
+            operands.len()
+        )));
+    }
+    let mut sizes: HashMap<char, u64> = HashMap::new();
+    for (k, (subs, operand)) in labels.iter().zip(operands).enumerate() {
+        let dims = operand.dims();
+        if subs.len() > 4 {
+            return Err(fold_error(subs));
+        }
+        if dims.get()[subs.len()..].iter().any(|&d| d != 1) {
+            return Err(size_error(format!(
+                "operand {} of dims {} has more dimensions than subscripts '{}'",
+                k,
+                dims,
+                subs.iter().collect::<String>()
+            )));
+        }
+        for (i, &label) in subs.iter().enumerate() {
+            if subs[..i].contains(&label) {
+                return Err(ArrayFireError::with_message(
+                    AfError::ERR_NOT_SUPPORTED,
+                    format!("subscript '{}' repeats within operand {}", label, k),
+                ));
+            }
+            let size = *sizes.entry(label).or_insert(dims[i]);
+            if size != dims[i] {
+                return Err(size_error(format!(
+                    "subscript '{}' has length {} in operand {}, but {} before",
+                    label, dims[i], k, size
+                )));
+            }
+        }
+    }
+    let terms = labels
+        .iter()
+        .zip(operands)
+        .map(|(subs, operand)| Term {
+            array: (*operand).clone(),
+            labels: subs.clone(),
+        })
+        .collect();
+    Ok((Contraction { sizes }, terms))
+}
+
+fn parse_labels(subscripts: &str) -> AfResult<Vec<char>> {
+    let labels: Vec<char> = subscripts.trim().chars().collect();
+    match labels.iter().find(|c| !c.is_ascii_alphabetic()) {
+        Some(c) => Err(arg_error(format!("invalid subscript '{}'", c))),
+        None => Ok(labels),
+    }
+}
+
+/// Evaluate an Einstein summation over Arrays
+///
+/// Each operand is described by one subscript label per dimension, in ArrayFire's dimension
+/// order, i.e. the first label identifies the rows. Labels shared by operands are multiplied
+/// elementwise; labels not present in the output are summed over. Without `->`, the output
+/// consists of the labels that appear exactly once, in alphabetical order.
+///
+/// Pairs of operands are contracted one at a time, picking the pair with the smallest
+/// intermediate result first. Each contraction is lowered to a (batched)
+/// [matmul](./fn.matmul.html), using [MatProp](./enum.MatProp.html) transposes instead of
+/// reordering operands whose dimensions are already grouped suitably. Labels shared by both
+/// operands and the output are batch dimensions.
+///
+/// # Parameters
+///
+/// - `subscripts` has the form `"ij,jk->ik"`
+/// - `operands` are the Arrays referred to by the comma separated subscripts
+///
+/// # Return Values
+///
+/// The result Array. Fails with `ERR_ARG` for malformed subscripts, `ERR_SIZE` if the lengths
+/// of dimensions sharing a label differ and `ERR_NOT_SUPPORTED` if a subscript is repeated
+/// within an operand or an intermediate result has more than four dimensions.
+///
+/// # Examples
+///
+/// ```rust
+/// use arrayfire::{einsum, randu, Dim4};
+/// // Batch of 8 matrix products, the batch is the third dimension
+/// let a = randu::<f32>(Dim4::new(&[3, 4, 8, 1]));
+/// let b = randu::<f32>(Dim4::new(&[4, 5, 8, 1]));
+/// let c = einsum("ijb,jkb->ikb", &[&a, &b]).unwrap();
+/// assert_eq!(c.dims(), Dim4::new(&[3, 5, 8, 1]));
+///
+/// // Trace of each matrix product
+/// let t = einsum("ijb,jib->b", &[&a, &randu::<f32>(Dim4::new(&[4, 3, 8, 1]))]).unwrap();
+/// assert_eq!(t.dims(), Dim4::new(&[8, 1, 1, 1]));
+/// ```
+pub fn einsum<T>(subscripts: &str, operands: &[&Array<T>]) -> AfResult<Array<T>>
+where
+    T: HasAfEnum<AggregateOutType = T> + FloatingPoint,
+{
+    let (inputs, output) = match subscripts.split_once("->") {
+        Some((inputs, output)) => (inputs, Some(output)),
+        None => (subscripts, None),
+    };
+    let labels = inputs
+        .split(',')
+        .map(parse_labels)
+        .collect::<AfResult<Vec<_>>>()?;
+    let output = match output {
+        Some(output) => parse_labels(output)?,
+        None => {
+            let mut once: Vec<char> = labels
+                .iter()
+                .flatten()
+                .copied()
+                .filter(|l| labels.iter().flatten().filter(|&m| m == l).count() == 1)
+                .collect();
+            once.sort_unstable();
+            once
+        }
+    };
+    for (i, label) in output.iter().enumerate() {
+        if output[..i].contains(label) {
+            return Err(arg_error(format!("output subscript '{}' repeats", label)));
+        }
+        if !labels.iter().flatten().any(|l| l == label) {
+            return Err(arg_error(format!(
+                "output subscript '{}' doesn't appear in the inputs",
+                label
+            )));
+        }
+    }
+    if output.len() > 4 {
+        return Err(fold_error(&output));
+    }
+
+    let (contraction, terms) = prepare(&labels, operands)?;
+    contraction.evaluate(terms, &output)
+}
+
+/// Contract two Arrays over pairs of dimensions
+///
+/// Dimension `lhs_axes[i]` of `lhs` is contracted with dimension `rhs_axes[i]` of `rhs`. The
+/// result has the remaining dimensions of `lhs` followed by the remaining dimensions of `rhs`,
+/// in their original order. See [einsum](./fn.einsum.html) for how the contraction is
+/// evaluated.
+///
+/// # Examples
+///
+/// ```rust
+/// use arrayfire::{randu, tensordot, Dim4};
+/// let a = randu::<f32>(Dim4::new(&[3, 4, 5, 1]));
+/// let b = randu::<f32>(Dim4::new(&[5, 4, 2, 1]));
+/// let c = tensordot(&a, &b, &[1, 2], &[1, 0]).unwrap();
+/// assert_eq!(c.dims(), Dim4::new(&[3, 2, 1, 1]));
+/// ```
+pub fn tensordot<T>(
+    lhs: &Array<T>,
+    rhs: &Array<T>,
+    lhs_axes: &[usize],
+    rhs_axes: &[usize],
+) -> AfResult<Array<T>>
+where
+    T: HasAfEnum<AggregateOutType = T> + FloatingPoint,
+{
+    if lhs_axes.len() != rhs_axes.len() {
+        return Err(arg_error(format!(
+            "{} axes of lhs paired with {} axes of rhs",
+            lhs_axes.len(),
+            rhs_axes.len()
+        )));
+    }
+    let ndims = |array: &Array<T>, axes: &[usize]| -> AfResult<usize> {
+        let max_axis = axes.iter().map(|&a| a + 1).max().unwrap_or(0);
+        if max_axis > 4 {
+            return Err(arg_error(format!("axes {:?} are out of bounds", axes)));
+        }
+        Ok(array.dims().ndims().max(max_axis))
+    };
+    let lhs_labels: Vec<char> = ('a'..='z').take(ndims(lhs, lhs_axes)?).collect();
+    let mut fresh = ('a'..='z').skip(lhs_labels.len());
+    let rhs_labels: Vec<char> = (0..ndims(rhs, rhs_axes)?)
+        .map(|axis| match rhs_axes.iter().position(|&a| a == axis) {
+            Some(pair) => lhs_labels[lhs_axes[pair]],
+            None => fresh.next().unwrap(),
+        })
+        .collect();
+    let output: Vec<char> = lhs_labels
+        .iter()
+        .enumerate()
+        .filter(|(axis, _)| !lhs_axes.contains(axis))
+        .map(|(_, &l)| l)
+        .chain(
+            rhs_labels
+                .iter()
+                .enumerate()
+                .filter(|(axis, _)| !rhs_axes.contains(axis))
+                .map(|(_, &l)| l),
+        )
+        .collect();
+    if output.len() > 4 {
+        return Err(fold_error(&output));
+    }
+
+    let (contraction, terms) = prepare(&[lhs_labels, rhs_labels], &[lhs, rhs])?;
+    contraction.evaluate(terms, &output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{einsum, tensordot};
+    use crate::core::{set_device, AfError, Array, Dim4};
+
+    type Operand<'a> = (&'a [char], &'a [u64], &'a [f32]);
+
+    fn to_vec(array: &Array<f32>) -> Vec<f32> {
+        let mut data = vec![0.0f32; array.elements()];
+        array.host(&mut data);
+        data
+    }
+
+    fn iota(dims: Dim4, offset: f32) -> (Array<f32>, Vec<f32>) {
+        let values: Vec<f32> = (0..dims.elements()).map(|v| v as f32 + offset).collect();
+        (Array::new(&values, dims), values)
+    }
+
+    /// Host evaluation of a contraction using explicit loops over all labels
+    fn reference(operands: &[Operand], output: &[char]) -> Vec<f32> {
+        let mut labels: Vec<char> = Vec::new();
+        let mut sizes: Vec<u64> = Vec::new();
+        for (subs, dims, _) in operands {
+            for (label, &len) in subs.iter().zip(dims.iter()) {
+                if !labels.contains(label) {
+                    labels.push(*label);
+                    sizes.push(len);
+                }
+            }
+        }
+        let size_of = |label: &char| sizes[labels.iter().position(|l| l == label).unwrap()];
+        let position = |subs: &[char], index: &[u64]| -> usize {
+            let mut pos = 0;
+            let mut stride = 1;
+            for label in subs {
+                pos += index[labels.iter().position(|l| l == label).unwrap()] * stride;
+                stride *= size_of(label);
+            }
+            pos as usize
+        };
+        let mut result = vec![0.0f32; output.iter().map(size_of).product::<u64>() as usize];
+        for flat in 0..sizes.iter().product::<u64>() {
+            let mut rest = flat;
+            let index: Vec<u64> = sizes
+                .iter()
+                .map(|len| {
+                    let i = rest % len;
+                    rest /= len;
+                    i
+                })
+                .collect();
+            let value: f32 = operands
+                .iter()
+                .map(|(subs, _, data)| data[position(subs, &index)])
+                .product();
+            result[position(output, &index)] += value;
+        }
+        result
+    }
+
+    #[test]
+    fn einsum_matches_reference() {
+        set_device(0);
+        let (a, av) = iota(crate::dim4!(2, 3, 2), 0.0);
+        let (b, bv) = iota(crate::dim4!(3, 4, 2), 1.0);
+        let (c, cv) = iota(crate::dim4!(3, 2), -2.0);
+        let (d, dv) = iota(crate::dim4!(4, 5), 0.5);
+        let (e, ev) = iota(crate::dim4!(3, 4), 2.0);
+
+        let batched = einsum("ijb,jkb->ikb", &[&a, &b]).unwrap();
+        assert_eq!(batched.dims(), crate::dim4!(2, 4, 2));
+        let expected = reference(
+            &[
+                (&['i', 'j', 'b'], &[2, 3, 2], &av),
+                (&['j', 'k', 'b'], &[3, 4, 2], &bv),
+            ],
+            &['i', 'k', 'b'],
+        );
+        assert_eq!(to_vec(&batched), expected);
+
+        let chain = einsum("ijb,jkb,kl->bil", &[&a, &b, &d]).unwrap();
+        let expected = reference(
+            &[
+                (&['i', 'j', 'b'], &[2, 3, 2], &av),
+                (&['j', 'k', 'b'], &[3, 4, 2], &bv),
+                (&['k', 'l'], &[4, 5], &dv),
+            ],
+            &['b', 'i', 'l'],
+        );
+        assert_eq!(to_vec(&chain), expected);
+
+        let implicit = einsum("ji,jk", &[&c, &e]).unwrap();
+        let expected = reference(
+            &[(&['j', 'i'], &[3, 2], &cv), (&['j', 'k'], &[3, 4], &ev)],
+            &['i', 'k'],
+        );
+        assert_eq!(to_vec(&implicit), expected);
+
+        let total = einsum("ijb->", &[&a]).unwrap();
+        assert_eq!(to_vec(&total), vec![av.iter().sum::<f32>()]);
+
+        let swapped = einsum("ijb->bji", &[&a]).unwrap();
+        let expected = reference(&[(&['i', 'j', 'b'], &[2, 3, 2], &av)], &['b', 'j', 'i']);
+        assert_eq!(to_vec(&swapped), expected);
+
+        let err = einsum("ij,jk", &[&c, &d]).unwrap_err();
+        assert_eq!(err.code(), AfError::ERR_SIZE);
+        let err = einsum("ij,jk->ik,", &[&c, &e]).unwrap_err();
+        assert_eq!(err.code(), AfError::ERR_ARG);
+        let err = einsum("ii->i", &[&c]).unwrap_err();
+        assert_eq!(err.code(), AfError::ERR_NOT_SUPPORTED);
+        let err = einsum("ijb,klm->ijbklm", &[&a, &b]).unwrap_err();
+        assert_eq!(err.code(), AfError::ERR_NOT_SUPPORTED);
+    }
+
+    #[test]
+    fn tensordot_matches_reference() {
+        set_device(0);
+        let (a, av) = iota(crate::dim4!(2, 3, 4), 0.0);
+        let (b, bv) = iota(crate::dim4!(4, 3, 5), 1.0);
+        let c = tensordot(&a, &b, &[1, 2], &[1, 0]).unwrap();
+        assert_eq!(c.dims(), crate::dim4!(2, 5));
+        let expected = reference(
+            &[
+                (&['a', 'b', 'c'], &[2, 3, 4], &av),
+                (&['c', 'b', 'd'], &[4, 3, 5], &bv),
+            ],
+            &['a', 'd'],
+        );
+        assert_eq!(to_vec(&c), expected);
+
+        let err = tensordot(&a, &b, &[0], &[0]).unwrap_err();
+        assert_eq!(err.code(), AfError::ERR_SIZE);
+    }
+}
@@ -5,6 +5,15 @@ use super::core::{
 use libc::{c_int, c_uint, c_void};
 use std::vec::Vec;
 
+#[cfg(feature = "data")]
+pub use batched::batched_matmul;
+#[cfg(all(feature = "algorithm", feature = "data"))]
+pub use einsum::{einsum, tensordot};
+#[cfg(feature = "data")]
+mod batched;
+#[cfg(all(feature = "algorithm", feature = "data"))]
+mod einsum;
+
 extern "C" {
     fn af_gemm(
         out: *mut af_array,
@@ -2,7 +2,7 @@ use super::array::Array;
 use super::data::{constant, tile, ConstGenerator};
 use super::defines::AfError;
 use super::dim4::Dim4;
-use super::error::HANDLE_ERROR;
+use super::error::{af_result, try_af, AfResult, ArrayFireError, HANDLE_ERROR};
 use super::util::{af_array, HasAfEnum, ImplicitPromote, IntegralType};
 
 use half::f16;
@@ -118,7 +118,7 @@ where
 }
 
 macro_rules! unary_func {
-    [$doc_str: expr, $fn_name: ident, $ffi_fn: ident, $out_type: ident] => (
+    [$doc_str: expr, $fn_name: ident, $try_name: ident, $ffi_fn: ident, $out_type: ident] => (
         #[doc=$doc_str]
         ///
         /// This is an element wise unary operation.
@@ -131,127 +131,216 @@ macro_rules! unary_func {
                 temp.into()
 
         }
+
+        #[doc = concat!(
+            "Same as [", stringify!($fn_name), "](./fn.", stringify!($fn_name), ".html), ",
+            "but returns failures as [ArrayFireError](./struct.ArrayFireError.html) instead of ",
+            "invoking the error handler."
+        )]
+        pub fn $try_name<T: HasAfEnum>(input: &Array<T>) -> AfResult<Array< T::$out_type >>
+        where T::$out_type: HasAfEnum {
+
+                let mut temp: af_array = std::ptr::null_mut();
+                let err_val = unsafe { $ffi_fn(&mut temp as *mut af_array, input.get()) };
+                af_result(AfError::from(err_val))?;
+                Ok(temp.into())
+
+        }
     )
 }
 
-unary_func!("Computes absolute value", abs, af_abs, AbsOutType);
-unary_func!("Computes phase value", arg, af_arg, ArgOutType);
+unary_func!("Computes absolute value", abs, try_abs, af_abs, AbsOutType);
+unary_func!("Computes phase value", arg, try_arg, af_arg, ArgOutType);
 
 unary_func!(
     "Truncate the values in an Array",
     trunc,
+    try_trunc,
     af_trunc,
     AbsOutType
 );
 unary_func!(
     "Computes the sign of input Array values",
     sign,
+    try_sign,
     af_sign,
     AbsOutType
 );
-unary_func!("Round the values in an Array", round, af_round, AbsOutType);
-unary_func!("Floor the values in an Array", floor, af_floor, AbsOutType);
-unary_func!("Ceil the values in an Array", ceil, af_ceil, AbsOutType);
+unary_func!(
+    "Round the values in an Array",
+    round,
+    try_round,
+    af_round,
+    AbsOutType
+);
+unary_func!(
+    "Floor the values in an Array",
+    floor,
+    try_floor,
+    af_floor,
+    AbsOutType
+);
+unary_func!(
+    "Ceil the values in an Array",
+    ceil,
+    try_ceil,
+    af_ceil,
+    AbsOutType
+);
 
-unary_func!("Compute sigmoid function", sigmoid, af_sigmoid, AbsOutType);
+unary_func!(
+    "Compute sigmoid function",
+    sigmoid,
+    try_sigmoid,
+    af_sigmoid,
+    AbsOutType
+);
 unary_func!(
     "Compute e raised to the power of value -1",
     expm1,
+    try_expm1,
     af_expm1,
     AbsOutType
 );
-unary_func!("Compute error function value", erf, af_erf, AbsOutType);
+unary_func!(
+    "Compute error function value",
+    erf,
+    try_erf,
+    af_erf,
+    AbsOutType
+);
 unary_func!(
     "Compute the complementary error function value",
     erfc,
+    try_erfc,
     af_erfc,
     AbsOutType
 );
 
-unary_func!("Compute logarithm base 10", log10, af_log10, AbsOutType);
+unary_func!(
+    "Compute logarithm base 10",
+    log10,
+    try_log10,
+    af_log10,
+    AbsOutType
+);
 unary_func!(
     "Compute the logarithm of input Array + 1",
     log1p,
+    try_log1p,
     af_log1p,
     AbsOutType
 );
-unary_func!("Compute logarithm base 2", log2, af_log2, AbsOutType);
+unary_func!(
+    "Compute logarithm base 2",
+    log2,
+    try_log2,
+    af_log2,
+    AbsOutType
+);
 
-unary_func!("Compute the cube root", cbrt, af_cbrt, AbsOutType);
-unary_func!("Compute gamma function", tgamma, af_tgamma, AbsOutType);
+unary_func!("Compute the cube root", cbrt, try_cbrt, af_cbrt, AbsOutType);
+unary_func!(
+    "Compute gamma function",
+    tgamma,
+    try_tgamma,
+    af_tgamma,
+    AbsOutType
+);
 unary_func!(
     "Compute the logarithm of absolute values of gamma function",
     lgamma,
+    try_lgamma,
     af_lgamma,
     AbsOutType
 );
 
-unary_func!("Compute acosh", acosh, af_acosh, UnaryOutType);
-unary_func!("Compute acos", acos, af_acos, UnaryOutType);
-unary_func!("Compute asin", asin, af_asin, UnaryOutType);
-unary_func!("Compute asinh", asinh, af_asinh, UnaryOutType);
-unary_func!("Compute atan", atan, af_atan, UnaryOutType);
-unary_func!("Compute atanh", atanh, af_atanh, UnaryOutType);
-unary_func!("Compute cos", cos, af_cos, UnaryOutType);
-unary_func!("Compute cosh", cosh, af_cosh, UnaryOutType);
+unary_func!("Compute acosh", acosh, try_acosh, af_acosh, UnaryOutType);
+unary_func!("Compute acos", acos, try_acos, af_acos, UnaryOutType);
+unary_func!("Compute asin", asin, try_asin, af_asin, UnaryOutType);
+unary_func!("Compute asinh", asinh, try_asinh, af_asinh, UnaryOutType);
+unary_func!("Compute atan", atan, try_atan, af_atan, UnaryOutType);
+unary_func!("Compute atanh", atanh, try_atanh, af_atanh, UnaryOutType);
+unary_func!("Compute cos", cos, try_cos, af_cos, UnaryOutType);
+unary_func!("Compute cosh", cosh, try_cosh, af_cosh, UnaryOutType);
 unary_func!(
     "Compute e raised to the power of value",
     exp,
+    try_exp,
     af_exp,
     UnaryOutType
 );
-unary_func!("Compute the natural logarithm", log, af_log, UnaryOutType);
-unary_func!("Compute sin", sin, af_sin, UnaryOutType);
-unary_func!("Compute sinh", sinh, af_sinh, UnaryOutType);
-unary_func!("Compute the square root", sqrt, af_sqrt, UnaryOutType);
+unary_func!(
+    "Compute the natural logarithm",
+    log,
+    try_log,
+    af_log,
+    UnaryOutType
+);
+unary_func!("Compute sin", sin, try_sin, af_sin, UnaryOutType);
+unary_func!("Compute sinh", sinh, try_sinh, af_sinh, UnaryOutType);
+unary_func!(
+    "Compute the square root",
+    sqrt,
+    try_sqrt,
+    af_sqrt,
+    UnaryOutType
+);
 unary_func!(
     "Compute the reciprocal square root",
     rsqrt,
+    try_rsqrt,
     af_rsqrt,
     UnaryOutType
 );
-unary_func!("Compute tan", tan, af_tan, UnaryOutType);
-unary_func!("Compute tanh", tanh, af_tanh, UnaryOutType);
+unary_func!("Compute tan", tan, try_tan, af_tan, UnaryOutType);
+unary_func!("Compute tanh", tanh, try_tanh, af_tanh, UnaryOutType);
 
 unary_func!(
     "Extract real values from a complex Array",
     real,
+    try_real,
     af_real,
     AbsOutType
 );
 unary_func!(
     "Extract imaginary values from a complex Array",
     imag,
+    try_imag,
     af_imag,
     AbsOutType
 );
 unary_func!(
     "Create a complex Array from real Array",
     cplx,
+    try_cplx,
     af_cplx,
     ComplexOutType
 );
 unary_func!(
     "Compute the complex conjugate",
     conjg,
+    try_conjg,
     af_conjg,
     ComplexOutType
 );
 unary_func!(
     "Compute two raised to the power of value",
     pow2,
+    try_pow2,
     af_pow2,
     UnaryOutType
 );
 unary_func!(
     "Compute the factorial",
     factorial,
+    try_factorial,
     af_factorial,
     UnaryOutType
 );
 
 macro_rules! unary_boolean_func {
-    [$doc_str: expr, $fn_name: ident, $ffi_fn: ident] => (
+    [$doc_str: expr, $fn_name: ident, $try_name: ident, $ffi_fn: ident] => (
         #[doc=$doc_str]
         ///
         /// This is an element wise unary operation.
@@ -263,15 +352,29 @@ macro_rules! unary_boolean_func {
                 temp.into()
 
         }
+
+        #[doc = concat!(
+            "Same as [", stringify!($fn_name), "](./fn.", stringify!($fn_name), ".html), ",
+            "but returns failures as [ArrayFireError](./struct.ArrayFireError.html) instead of ",
+            "invoking the error handler."
+        )]
+        pub fn $try_name<T: HasAfEnum>(input: &Array<T>) -> AfResult<Array<bool>> {
+
+                let mut temp: af_array = std::ptr::null_mut();
+                let err_val = unsafe { $ffi_fn(&mut temp as *mut af_array, input.get()) };
+                af_result(AfError::from(err_val))?;
+                Ok(temp.into())
+
+        }
     )
 }
 
-unary_boolean_func!("Check if values are zero", iszero, af_iszero);
-unary_boolean_func!("Check if values are infinity", isinf, af_isinf);
-unary_boolean_func!("Check if values are NaN", isnan, af_isnan);
+unary_boolean_func!("Check if values are zero", iszero, try_iszero, af_iszero);
+unary_boolean_func!("Check if values are infinity", isinf, try_isinf, af_isinf);
+unary_boolean_func!("Check if values are NaN", isnan, try_isnan, af_isnan);
 
 macro_rules! binary_func {
-    ($doc_str: expr, $fn_name: ident, $ffi_fn: ident) => {
+    ($doc_str: expr, $fn_name: ident, $try_name: ident, $ffi_fn: ident) => {
         #[doc=$doc_str]
         ///
         /// This is an element wise binary operation.
@@ -297,37 +400,60 @@ macro_rules! binary_func {
             HANDLE_ERROR(AfError::from(err_val));
             Into::<Array<A::Output>>::into(temp)
         }
+
+        #[doc = concat!(
+            "Same as [", stringify!($fn_name), "](./fn.", stringify!($fn_name), ".html), ",
+            "but returns failures as [ArrayFireError](./struct.ArrayFireError.html) instead of ",
+            "invoking the error handler."
+        )]
+        pub fn $try_name<A, B>(lhs: &Array<A>, rhs: &Array<B>, batch: bool) -> AfResult<Array<A::Output>>
+        where
+            A: ImplicitPromote<B>,
+            B: ImplicitPromote<A>,
+        {
+            let mut temp: af_array = std::ptr::null_mut();
+            let err_val =
+                unsafe { $ffi_fn(&mut temp as *mut af_array, lhs.get(), rhs.get(), batch) };
+            af_result(AfError::from(err_val))?;
+            Ok(Into::<Array<A::Output>>::into(temp))
+        }
     };
 }
 
 binary_func!(
     "Elementwise AND(bit) operation of two Arrays",
     bitand,
+    try_bitand,
     af_bitand
 );
 binary_func!(
     "Elementwise OR(bit) operation of two Arrays",
     bitor,
+    try_bitor,
     af_bitor
 );
 binary_func!(
     "Elementwise XOR(bit) operation of two Arrays",
     bitxor,
+    try_bitxor,
     af_bitxor
 );
 binary_func!(
     "Elementwise minimum operation of two Arrays",
     minof,
+    try_minof,
     af_minof
 );
 binary_func!(
     "Elementwise maximum operation of two Arrays",
     maxof,
+    try_maxof,
     af_maxof
 );
 binary_func!(
     "Compute length of hypotenuse of two Arrays",
     hypot,
+    try_hypot,
     af_hypot
 );
 
@@ -382,7 +508,7 @@ impl<T: HasAfEnum> Convertable for Array<T> {
 }
 
 macro_rules! overloaded_binary_func {
-    ($doc_str: expr, $fn_name: ident, $help_name: ident, $ffi_name: ident) => {
+    ($doc_str: expr, $fn_name: ident, $try_name: ident, $help_name: ident, $ffi_name: ident) => {
         fn $help_name<A, B>(lhs: &Array<A>, rhs: &Array<B>, batch: bool) -> Array<A::Output>
         where
             A: ImplicitPromote<B>,
@@ -451,39 +577,174 @@ macro_rules! overloaded_binary_func {
                 _ => $help_name(&lhs, &rhs, batch),
             }
         }
+
+        #[doc = concat!(
+            "Same as [", stringify!($fn_name), "](./fn.", stringify!($fn_name), ".html), ",
+            "but returns failures as [ArrayFireError](./struct.ArrayFireError.html) instead of ",
+            "invoking the error handler."
+        )]
+        pub fn $try_name<T, U>(
+            arg1: &T,
+            arg2: &U,
+            batch: bool,
+        ) -> AfResult<
+            Array<
+                <<T as Convertable>::OutType as ImplicitPromote<<U as Convertable>::OutType>>::Output,
+            >,
+        >
+        where
+            T: Convertable,
+            U: Convertable,
+            <T as Convertable>::OutType: ImplicitPromote<<U as Convertable>::OutType>,
+            <U as Convertable>::OutType: ImplicitPromote<<T as Convertable>::OutType>,
+        {
+            try_af(|| $fn_name(arg1, arg2, batch))
+        }
     };
 }
 
-overloaded_binary_func!("Addition of two Arrays", add, add_helper, af_add);
-overloaded_binary_func!("Subtraction of two Arrays", sub, sub_helper, af_sub);
-overloaded_binary_func!("Multiplication of two Arrays", mul, mul_helper, af_mul);
-overloaded_binary_func!("Division of two Arrays", div, div_helper, af_div);
-overloaded_binary_func!("Compute remainder from two Arrays", rem, rem_helper, af_rem);
-overloaded_binary_func!("Compute left shift", shiftl, shiftl_helper, af_bitshiftl);
-overloaded_binary_func!("Compute right shift", shiftr, shiftr_helper, af_bitshiftr);
+overloaded_binary_func!("Addition of two Arrays", add, try_add, add_helper, af_add);
+overloaded_binary_func!(
+    "Subtraction of two Arrays",
+    sub,
+    try_sub,
+    sub_helper,
+    af_sub
+);
+overloaded_binary_func!(
+    "Multiplication of two Arrays",
+    mul,
+    try_mul,
+    mul_helper,
+    af_mul
+);
+overloaded_binary_func!("Division of two Arrays", div, try_div, div_helper, af_div);
+overloaded_binary_func!(
+    "Compute remainder from two Arrays",
+    rem,
+    try_rem,
+    rem_helper,
+    af_rem
+);
+overloaded_binary_func!(
+    "Compute left shift",
+    shiftl,
+    try_shiftl,
+    shiftl_helper,
+    af_bitshiftl
+);
+overloaded_binary_func!(
+    "Compute right shift",
+    shiftr,
+    try_shiftr,
+    shiftr_helper,
+    af_bitshiftr
+);
 overloaded_binary_func!(
     "Compute modulo of two Arrays",
     modulo,
+    try_modulo,
     modulo_helper,
     af_mod
 );
 overloaded_binary_func!(
     "Calculate atan2 of two Arrays",
     atan2,
+    try_atan2,
     atan2_helper,
     af_atan2
 );
 overloaded_binary_func!(
     "Create complex array from two Arrays",
     cplx2,
+    try_cplx2,
     cplx2_helper,
     af_cplx2
 );
-overloaded_binary_func!("Compute root", root, root_helper, af_root);
-overloaded_binary_func!("Computer power", pow, pow_helper, af_pow);
+overloaded_binary_func!("Compute root", root, try_root, root_helper, af_root);
+overloaded_binary_func!("Computer power", pow, try_pow, pow_helper, af_pow);
+
+/// Returns the dimensions resulting from broadcasting `lhs` and `rhs` against each other
+///
+/// Following NumPy's rules, dimensions are compatible when they are equal or one of them is
+/// one, in which case the operand is expanded along that dimension. Since Dim4 pads missing
+/// dimensions with one, an Array of dims `[n, 1, 1, 1]` broadcasts against any Array with `n`
+/// rows.
+///
+/// # Return Values
+///
+/// The dimensions of the broadcast result. Fails with `ERR_SIZE` if the dimensions are
+/// incompatible.
+///
+/// # Examples
+///
+/// ```rust
+/// use arrayfire::{broadcast_dims, dim4};
+/// assert_eq!(broadcast_dims(dim4!(3, 1), dim4!(1, 4)).unwrap(), dim4!(3, 4));
+/// assert!(broadcast_dims(dim4!(3, 2), dim4!(2, 3)).is_err());
+/// ```
+pub fn broadcast_dims(lhs: Dim4, rhs: Dim4) -> AfResult<Dim4> {
+    let mut dims = [1u64; 4];
+    for (i, dim) in dims.iter_mut().enumerate() {
+        *dim = match (lhs[i], rhs[i]) {
+            (l, r) if l == r => l,
+            (1, r) => r,
+            (l, 1) => l,
+            _ => {
+                return Err(ArrayFireError::with_message(
+                    AfError::ERR_SIZE,
+                    format!("shapes {} and {} can not be broadcast together", lhs, rhs),
+                ))
+            }
+        };
+    }
+    Ok(Dim4::new(&dims))
+}
+
+macro_rules! broadcast_binary_func {
+    ($doc_str: expr, $fn_name: ident, $help_name: ident) => {
+        #[doc=$doc_str]
+        ///
+        /// This is a binary elementwise operation with NumPy style broadcasting, see
+        /// [broadcast_dims](./fn.broadcast_dims.html) for the rules.
+        ///
+        ///# Parameters
+        ///
+        /// - `lhs` is the first Array
+        /// - `rhs` is the second Array
+        ///
+        ///# Return Values
+        ///
+        /// An Array with the broadcast dimensions of `lhs` and `rhs`. Fails with `ERR_SIZE`,
+        /// without calling into ArrayFire, if the dimensions are incompatible.
+        pub fn $fn_name<A, B>(lhs: &Array<A>, rhs: &Array<B>) -> AfResult<Array<A::Output>>
+        where
+            A: ImplicitPromote<B>,
+            B: ImplicitPromote<A>,
+        {
+            let dims = broadcast_dims(lhs.dims(), rhs.dims())?;
+            if dims.elements() == 0 {
+                return Ok(Array::new_empty(dims));
+            }
+            // Batch mode expands singleton dimensions without materializing tiled copies
+            Ok($help_name(lhs, rhs, true))
+        }
+    };
+}
+
+broadcast_binary_func!("Addition of two Arrays", broadcast_add, add_helper);
+broadcast_binary_func!("Subtraction of two Arrays", broadcast_sub, sub_helper);
+broadcast_binary_func!("Multiplication of two Arrays", broadcast_mul, mul_helper);
+broadcast_binary_func!("Division of two Arrays", broadcast_div, div_helper);
+broadcast_binary_func!(
+    "Compute remainder from two Arrays",
+    broadcast_rem,
+    rem_helper
+);
+broadcast_binary_func!("Compute power", broadcast_pow, pow_helper);
 
 macro_rules! overloaded_logic_func {
-    ($doc_str: expr, $fn_name: ident, $help_name: ident, $ffi_name: ident) => {
+    ($doc_str: expr, $fn_name: ident, $try_name: ident, $help_name: ident, $ffi_name: ident) => {
         fn $help_name<A, B>(lhs: &Array<A>, rhs: &Array<B>, batch: bool) -> Array<bool>
         where
             A: ImplicitPromote<B>,
@@ -546,54 +807,77 @@ macro_rules! overloaded_logic_func {
                 _ => $help_name(&lhs, &rhs, batch),
             }
         }
+
+        #[doc = concat!(
+            "Same as [", stringify!($fn_name), "](./fn.", stringify!($fn_name), ".html), ",
+            "but returns failures as [ArrayFireError](./struct.ArrayFireError.html) instead of ",
+            "invoking the error handler."
+        )]
+        pub fn $try_name<T, U>(arg1: &T, arg2: &U, batch: bool) -> AfResult<Array<bool>>
+        where
+            T: Convertable,
+            U: Convertable,
+            <T as Convertable>::OutType: ImplicitPromote<<U as Convertable>::OutType>,
+            <U as Convertable>::OutType: ImplicitPromote<<T as Convertable>::OutType>,
+        {
+            try_af(|| $fn_name(arg1, arg2, batch))
+        }
     };
 }
 
 overloaded_logic_func!(
     "Perform `less than` comparison operation",
     lt,
+    try_lt,
     lt_helper,
     af_lt
 );
 overloaded_logic_func!(
     "Perform `greater than` comparison operation",
     gt,
+    try_gt,
     gt_helper,
     af_gt
 );
 overloaded_logic_func!(
     "Perform `less than equals` comparison operation",
     le,
+    try_le,
     le_helper,
     af_le
 );
 overloaded_logic_func!(
     "Perform `greater than equals` comparison operation",
     ge,
+    try_ge,
     ge_helper,
     af_ge
 );
 overloaded_logic_func!(
     "Perform `equals` comparison operation",
     eq,
+    try_eq,
     eq_helper,
     af_eq
 );
 overloaded_logic_func!(
     "Elementwise `not equals` comparison of two Arrays",
     neq,
+    try_neq,
     neq_helper,
     af_neq
 );
 overloaded_logic_func!(
     "Elementwise logical AND operation of two Arrays",
     and,
+    try_and,
     and_helper,
     af_and
 );
 overloaded_logic_func!(
     "Elementwise logical OR operation of two Arrays",
     or,
+    try_or,
     or_helper,
     af_or
 );
@@ -1,6 +1,6 @@
 use super::defines::{AfError, Backend, DType};
 use super::dim4::Dim4;
-use super::error::HANDLE_ERROR;
+use super::error::{handle_wrapper_error, AfResult, ArrayFireError, HANDLE_ERROR};
 use super::util::{af_array, dim_t, free_host, void_ptr, HasAfEnum};
 
 use libc::{c_char, c_int, c_longlong, c_uint, c_void};
@@ -10,8 +10,6 @@ use std::marker::PhantomData;
 
 // Some unused functions from array.h in C-API of ArrayFire
 // af_copy_array
-// af_write_array
-// af_get_data_ref_count
 
 extern "C" {
     fn af_create_array(
@@ -87,6 +85,12 @@ extern "C" {
 
     fn af_get_data_ptr(data: *mut c_void, arr: af_array) -> c_int;
 
+    fn af_write_array(arr: af_array, data: *const c_void, bytes: usize, src: c_uint) -> c_int;
+
+    fn af_get_data_ref_count(use_count: *mut c_int, arr: af_array) -> c_int;
+
+    fn af_get_scalar(output_value: *mut c_void, arr: af_array) -> c_int;
+
     fn af_eval(arr: af_array) -> c_int;
 
     fn af_eval_multiple(num: c_int, arrays: *const af_array) -> c_int;
@@ -523,13 +527,68 @@ where
     /// ```
     pub fn host<O: HasAfEnum>(&self, data: &mut [O]) {
         if data.len() != self.elements() {
-            HANDLE_ERROR(AfError::ERR_SIZE);
+            handle_wrapper_error(
+                AfError::ERR_SIZE,
+                &format!(
+                    "host buffer of {} elements can not hold an Array of {} elements",
+                    data.len(),
+                    self.elements()
+                ),
+            );
+            return;
         }
 
         let err_val = unsafe { af_get_data_ptr(data.as_mut_ptr() as *mut c_void, self.handle) };
         HANDLE_ERROR(AfError::from(err_val));
     }
 
+    /// Copy the value of a single element Array to host
+    ///
+    /// This avoids allocating a host buffer, as [host](./struct.Array.html#method.host)
+    /// requires, when fetching the result of a reduction.
+    ///
+    /// # Return Values
+    ///
+    /// The element of the Array. Fails with `ERR_SIZE` if the Array doesn't have exactly one
+    /// element and with `ERR_TYPE` if the Array's data type doesn't match `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arrayfire::{sum, Array, Dim4};
+    /// let a = Array::new(&[1u64, 2, 3], Dim4::new(&[3, 1, 1, 1]));
+    /// assert_eq!(sum(&a, 0).scalar().unwrap(), 6);
+    /// assert!(a.scalar().is_err());
+    /// ```
+    pub fn scalar(&self) -> AfResult<T> {
+        let stored = self.get_type();
+        if stored != T::get_af_dtype() {
+            return Err(ArrayFireError::with_message(
+                AfError::ERR_TYPE,
+                format!(
+                    "Array holds {:?} values, requested {:?}",
+                    stored,
+                    T::get_af_dtype()
+                ),
+            ));
+        }
+        let elements = self.elements();
+        if elements != 1 {
+            return Err(ArrayFireError::with_message(
+                AfError::ERR_SIZE,
+                format!(
+                    "scalar requires an Array with one element, Array of dims {} has {}",
+                    self.dims(),
+                    elements
+                ),
+            ));
+        }
+        let mut value = T::default();
+        let err_val = unsafe { af_get_scalar(&mut value as *mut T as *mut c_void, self.handle) };
+        check_err(err_val)?;
+        Ok(value)
+    }
+
     /// Evaluates any pending lazy expressions that represent the data in the Array object
     pub fn eval(&self) {
         let err_val = unsafe { af_eval(self.handle) };
@@ -662,6 +721,155 @@ where
         temp
     }
 
+    /// Overwrite the Array's data with values from host memory
+    ///
+    /// Unlike [Array::new](./struct.Array.html#method.new), the data is copied into the
+    /// existing device buffer. A new buffer is allocated only when the buffer is shared with
+    /// other Arrays, e.g. clones of this Array, which keep their values.
+    ///
+    /// The data has been copied once this function returns, hence `data` can be modified or
+    /// freed right away. This holds for host buffers allocated using
+    /// [alloc_pinned](./fn.alloc_pinned.html) as well.
+    ///
+    /// # Parameters
+    ///
+    /// - `data` is the host data in column major order, its length has to match the
+    ///   number of elements in the Array
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arrayfire::{af_print, Array, Dim4};
+    /// let mut frame = Array::new(&[0.0f32; 6], Dim4::new(&[2, 3, 1, 1]));
+    /// for i in 0..3 {
+    ///     let values = vec![i as f32; 6];
+    ///     frame.write_from_host(&values).unwrap();
+    ///     af_print!("frame", frame);
+    /// }
+    /// ```
+    ///
+    /// Using pinned host memory
+    ///
+    /// ```rust
+    /// use arrayfire::{alloc_pinned, free_pinned, Array, Dim4};
+    /// let mut frame = Array::new(&[0.0f32; 1024], Dim4::new(&[1024, 1, 1, 1]));
+    /// unsafe {
+    ///     let ptr = alloc_pinned(1024 * std::mem::size_of::<f32>()) as *mut f32;
+    ///     let pinned = std::slice::from_raw_parts_mut(ptr, 1024);
+    ///     pinned.iter_mut().for_each(|v| *v = 1.0);
+    ///     frame.write_from_host(pinned).unwrap();
+    ///     free_pinned(ptr as _);
+    /// }
+    /// ```
+    pub fn write_from_host(&mut self, data: &[T]) -> AfResult<()> {
+        self.check_write(0, data.len())?;
+        if data.len() != self.elements() {
+            return Err(ArrayFireError::with_message(
+                AfError::ERR_SIZE,
+                format!(
+                    "host data has {} elements, Array has {}",
+                    data.len(),
+                    self.elements()
+                ),
+            ));
+        }
+        self.write_prefix(data)
+    }
+
+    /// Overwrite a contiguous range of the Array's data with values from host memory
+    ///
+    /// The elements at linear (column major) positions `offset..offset + data.len()` are
+    /// replaced, the remaining elements keep their values. When `offset` is zero, the data is
+    /// copied straight into the existing device buffer as in
+    /// [write_from_host](./struct.Array.html#method.write_from_host). Otherwise only a
+    /// temporary buffer of `data.len()` elements is allocated.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arrayfire::{Array, Dim4};
+    /// let mut a = Array::new(&[0u32; 6], Dim4::new(&[2, 3, 1, 1]));
+    /// a.write_from_host_at(2, &[5, 6]).unwrap();
+    ///
+    /// let mut host = vec![0u32; 6];
+    /// a.host(&mut host);
+    /// assert_eq!(host, vec![0, 0, 5, 6, 0, 0]);
+    /// ```
+    pub fn write_from_host_at(&mut self, offset: usize, data: &[T]) -> AfResult<()> {
+        self.check_write(offset, data.len())?;
+        if data.is_empty() {
+            Ok(())
+        } else if offset == 0 {
+            self.write_prefix(data)
+        } else {
+            self.write_range(offset, data)
+        }
+    }
+
+    fn check_write(&self, offset: usize, len: usize) -> AfResult<()> {
+        let stored = self.get_type();
+        if stored != T::get_af_dtype() {
+            return Err(ArrayFireError::with_message(
+                AfError::ERR_TYPE,
+                format!(
+                    "Array holds {:?} values, host data is {:?}",
+                    stored,
+                    T::get_af_dtype()
+                ),
+            ));
+        }
+        let elements = self.elements();
+        match offset.checked_add(len) {
+            Some(end) if end <= elements => Ok(()),
+            _ => Err(ArrayFireError::with_message(
+                AfError::ERR_SIZE,
+                format!(
+                    "cannot write {} elements at offset {} into an Array of {} elements",
+                    len, offset, elements
+                ),
+            )),
+        }
+    }
+
+    /// Copies `data` to the start of the Array's buffer, detaching shared buffers first
+    fn write_prefix(&mut self, data: &[T]) -> AfResult<()> {
+        let mut use_count: c_int = 0;
+        let err_val = unsafe { af_get_data_ref_count(&mut use_count as *mut c_int, self.handle) };
+        check_err(err_val)?;
+        if use_count > 1 {
+            *self = self.copy();
+        }
+        // Source is afHost
+        let err_val = unsafe {
+            af_write_array(
+                self.handle,
+                data.as_ptr() as *const c_void,
+                std::mem::size_of_val(data),
+                1,
+            )
+        };
+        check_err(err_val)
+    }
+
+    #[cfg(feature = "indexing")]
+    fn write_range(&mut self, offset: usize, data: &[T]) -> AfResult<()> {
+        use super::index::assign_seq;
+        use super::seq::Seq;
+
+        let values = Array::new(data, Dim4::new(&[data.len() as u64, 1, 1, 1]));
+        let range = Seq::new(offset as f64, (offset + data.len() - 1) as f64, 1.0);
+        super::error::try_af(|| assign_seq(self, &[range], &values))
+    }
+
+    #[cfg(not(feature = "indexing"))]
+    fn write_range(&mut self, offset: usize, data: &[T]) -> AfResult<()> {
+        let _ = (offset, data);
+        Err(ArrayFireError::with_message(
+            AfError::ERR_NOT_SUPPORTED,
+            "writing at an offset requires the indexing feature",
+        ))
+    }
+
     /// Fetch Array as String
     #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
@@ -683,6 +891,13 @@ where
     }
 }
 
+fn check_err(err_val: c_int) -> AfResult<()> {
+    match AfError::from(err_val) {
+        AfError::SUCCESS => Ok(()),
+        err => Err(ArrayFireError::new(err)),
+    }
+}
+
 /// Used for creating Array object from native
 /// resource id, an 64 bit integer
 #[allow(clippy::from_over_into)]
@@ -952,6 +1167,160 @@ mod afserde {
     }
 }
 
+#[cfg(feature = "afndarray")]
+mod afndarray {
+    // Reimport required from super scope
+    use super::super::error::ArrayFireError;
+    use super::{AfError, Array, Dim4, HasAfEnum};
+
+    use ndarray::{ArrayBase, Data, Dimension, IxDyn, ShapeBuilder};
+    use std::convert::TryFrom;
+
+    /// Builds an Array preserving logical indexing of `input`
+    ///
+    /// Element at index `[i, j, k, l]` of `input` is found at position `(i, j, k, l)`
+    /// of the resulting Array. Layouts whose first axis is contiguous and whose strides are
+    /// positive are copied as is using [new_strided](./struct.Array.html#method.new_strided),
+    /// any other layout is first gathered into a column major buffer on host.
+    fn from_ndarray<T, S, D>(input: &ArrayBase<S, D>) -> Result<Array<T>, ArrayFireError>
+    where
+        T: HasAfEnum,
+        S: Data<Elem = T>,
+        D: Dimension,
+    {
+        if input.ndim() > 4 {
+            return Err(ArrayFireError::with_message(
+                AfError::ERR_NOT_SUPPORTED,
+                format!(
+                    "ndarray with {} dimensions can not be converted, ArrayFire supports at most 4",
+                    input.ndim()
+                ),
+            ));
+        }
+        let mut dims = [1u64; 4];
+        let mut strides = [1u64; 4];
+        for (axis, (&len, &stride)) in input.shape().iter().zip(input.strides()).enumerate() {
+            dims[axis] = len as u64;
+            strides[axis] = stride as u64;
+        }
+        let dims = Dim4::new(&dims);
+
+        let strided = !input.is_empty()
+            && input.strides().iter().all(|&stride| stride > 0)
+            && input.strides().first().copied().unwrap_or(1) == 1;
+        if strided {
+            let span = input
+                .shape()
+                .iter()
+                .zip(input.strides())
+                .map(|(&len, &stride)| (len - 1) * stride as usize)
+                .sum::<usize>()
+                + 1;
+            // Strides are positive, hence every element of input lies within `span` elements
+            // starting from the first one, all of which belong to the same allocation.
+            let slice = unsafe { std::slice::from_raw_parts(input.as_ptr(), span) };
+            Ok(Array::new_strided(slice, 0, dims, Dim4::new(&strides)))
+        } else {
+            // Iterating over reversed axes visits elements in column major order
+            let data: Vec<T> = input.view().reversed_axes().iter().cloned().collect();
+            Ok(Array::new(&data, dims))
+        }
+    }
+
+    macro_rules! from_ndarray_impl {
+        ($($dim: ty),+) => {
+            $(
+                /// Conversion from ndarray with at most 4 dimensions, preserving element indices
+                impl<T, S> From<&ArrayBase<S, $dim>> for Array<T>
+                where
+                    T: HasAfEnum,
+                    S: Data<Elem = T>,
+                {
+                    fn from(input: &ArrayBase<S, $dim>) -> Self {
+                        match from_ndarray(input) {
+                            Ok(array) => array,
+                            Err(_) => unreachable!("dimension count is checked at compile time"),
+                        }
+                    }
+                }
+            )+
+        };
+    }
+
+    from_ndarray_impl!(
+        ndarray::Ix0,
+        ndarray::Ix1,
+        ndarray::Ix2,
+        ndarray::Ix3,
+        ndarray::Ix4
+    );
+
+    macro_rules! try_from_ndarray_impl {
+        ($($dim: ty),+) => {
+            $(
+                /// Conversion from ndarray, fails if it has more than 4 dimensions
+                impl<T, S> TryFrom<&ArrayBase<S, $dim>> for Array<T>
+                where
+                    T: HasAfEnum,
+                    S: Data<Elem = T>,
+                {
+                    type Error = ArrayFireError;
+
+                    fn try_from(input: &ArrayBase<S, $dim>) -> Result<Self, Self::Error> {
+                        from_ndarray(input)
+                    }
+                }
+            )+
+        };
+    }
+
+    try_from_ndarray_impl!(IxDyn, ndarray::Ix5, ndarray::Ix6);
+
+    /// Conversion to ndarray, preserving element indices
+    ///
+    /// For ndarray types with fixed number of dimensions N, ArrayFire dimensions beyond N are
+    /// required to be of unit length. For [ArrayD](https://docs.rs/ndarray/latest/ndarray/type.ArrayD.html),
+    /// trailing dimensions of unit length are dropped, leaving at least one dimension.
+    impl<T, D> TryFrom<&Array<T>> for ndarray::Array<T, D>
+    where
+        T: HasAfEnum,
+        D: Dimension,
+    {
+        type Error = ArrayFireError;
+
+        fn try_from(input: &Array<T>) -> Result<Self, Self::Error> {
+            let dims = input.dims();
+            let used = dims
+                .get()
+                .iter()
+                .rposition(|&d| d != 1)
+                .map_or(0, |pos| pos + 1);
+            let ndim = D::NDIM.unwrap_or_else(|| used.max(1));
+            if ndim < used {
+                return Err(ArrayFireError::with_message(
+                    AfError::ERR_SIZE,
+                    format!(
+                        "Array of dimensions {} can not be converted to ndarray with {} dimensions",
+                        dims, ndim
+                    ),
+                ));
+            }
+            let shape: Vec<usize> = (0..ndim)
+                .map(|axis| if axis < 4 { dims[axis] as usize } else { 1 })
+                .collect();
+
+            let mut data = vec![T::default(); input.elements()];
+            input.host(&mut data);
+
+            let shape = D::from_dimension(&IxDyn(&shape)).ok_or_else(|| {
+                ArrayFireError::with_message(AfError::ERR_SIZE, "invalid ndarray dimensions")
+            })?;
+            ndarray::Array::from_shape_vec(shape.f(), data)
+                .map_err(|err| ArrayFireError::with_message(AfError::ERR_SIZE, err.to_string()))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::array::print;
@@ -1184,6 +1553,42 @@ mod tests {
         // ANCHOR_END: accum_using_channel
     }
 
+    #[test]
+    fn write_from_host_reuses_array() {
+        set_device(0);
+        let mut a = super::Array::new(&[0.0f32; 6], dim4!(2, 3));
+        let shared = a.clone();
+        let mut host = vec![0.0f32; 6];
+
+        a.write_from_host(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+        a.host(&mut host);
+        assert_eq!(host, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        shared.host(&mut host);
+        assert_eq!(host, vec![0.0; 6]);
+
+        a.write_from_host_at(0, &[7.0]).unwrap();
+        a.write_from_host_at(4, &[8.0, 9.0]).unwrap();
+        a.host(&mut host);
+        assert_eq!(host, vec![7.0, 2.0, 3.0, 4.0, 8.0, 9.0]);
+
+        let err = a.write_from_host(&[1.0; 5]).unwrap_err();
+        assert_eq!(err.code(), crate::AfError::ERR_SIZE);
+        let err = a.write_from_host_at(5, &[1.0; 2]).unwrap_err();
+        assert_eq!(err.code(), crate::AfError::ERR_SIZE);
+    }
+
+    #[test]
+    fn scalar_of_single_element_array() {
+        set_device(0);
+        let a = super::Array::new(&[u64::MAX - 1], dim4!(1));
+        assert_eq!(a.scalar().unwrap(), u64::MAX - 1);
+
+        let b = super::Array::new(&[1.0f32, 2.0], dim4!(2));
+        assert_eq!(b.scalar().unwrap_err().code(), crate::AfError::ERR_SIZE);
+        let empty = super::Array::<f32>::new_empty(dim4!(0));
+        assert_eq!(empty.scalar().unwrap_err().code(), crate::AfError::ERR_SIZE);
+    }
+
     #[cfg(feature = "afserde")]
     mod serde_tests {
         use super::super::Array;
@@ -1220,4 +1625,62 @@ mod tests {
             assert_eq!(sum_all(&(input - decoded)), (0u32, 0u32));
         }
     }
+
+    #[cfg(feature = "afndarray")]
+    mod ndarray_tests {
+        use super::super::Array;
+        use crate::core::set_device;
+        use crate::dim4;
+        use ndarray::{arr2, s, Array0, Array2, Array3, ArrayD, IxDyn};
+        use std::convert::TryFrom;
+
+        #[test]
+        fn ndarray_roundtrip_preserves_indices() {
+            set_device(0);
+            let input = arr2(&[[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+            let array = Array::from(&input);
+            assert_eq!(array.dims(), dim4!(2, 3));
+
+            let mut host = vec![0.0f32; 6];
+            array.host(&mut host);
+            assert_eq!(host, vec![1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+
+            let output = Array2::<f32>::try_from(&array).unwrap();
+            assert_eq!(output, input);
+        }
+
+        #[test]
+        fn ndarray_non_contiguous_views() {
+            set_device(0);
+            let input = Array3::from_shape_fn((4, 5, 2), |(i, j, k)| (i * 100 + j * 10 + k) as i32);
+            for view in [
+                input.slice(s![..;2, 1.., ..]),
+                input.slice(s![.., ..;-1, 1..]),
+                input.view().permuted_axes([2, 0, 1]),
+            ] {
+                let array = Array::from(&view);
+                let output = Array3::<i32>::try_from(&array).unwrap();
+                assert_eq!(output, view);
+            }
+        }
+
+        #[test]
+        fn ndarray_dimension_limits() {
+            set_device(0);
+            let five = ArrayD::<f32>::zeros(IxDyn(&[2, 1, 2, 1, 2]));
+            let err = Array::try_from(&five).unwrap_err();
+            assert_eq!(err.code(), crate::AfError::ERR_NOT_SUPPORTED);
+
+            let array = Array::new(&[0.0f32; 8], dim4!(2, 2, 2));
+            assert!(Array2::<f32>::try_from(&array).is_err());
+            let dynamic = ArrayD::<f32>::try_from(&array).unwrap();
+            assert_eq!(dynamic.shape(), &[2, 2, 2]);
+
+            let single = Array::new(&[7.0f32], dim4!(1));
+            let output = Array0::<f32>::try_from(&single).unwrap();
+            assert_eq!(output[()], 7.0);
+            assert_eq!(ArrayD::<f32>::try_from(&single).unwrap().shape(), &[1]);
+            assert!(Array0::<f32>::try_from(&array).is_err());
+        }
+    }
 }
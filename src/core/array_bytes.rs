@@ -0,0 +1,478 @@
+//! Byte oriented serialization of [Array](../struct.Array.html) objects
+//!
+//! The default serde implementation of Array copies the data into a `Vec<T>` and serializes
+//! it element by element, which is slow and doubles the memory usage for large arrays. This
+//! module serializes the data as a single byte buffer using `serialize_bytes` instead and is
+//! meant to be used with serde's `with` attribute.
+//!
+//! The serialized form is a struct with the following fields:
+//!
+//! - `dtype`: [DType](../enum.DType.html) of the elements
+//! - `shape`: [Dim4](../struct.Dim4.html) holding the dimensions
+//! - `strides`: [Dim4](../struct.Dim4.html) holding the distance, in elements, between
+//!   consecutive elements along each dimension
+//! - `offset`: position, in elements, of the first element within `data`
+//! - `data`: the buffer, elements stored in **little endian** byte order irrespective of the
+//!   platform. Real and imaginary parts of complex numbers are stored one after the other.
+//!
+//! Arrays created using [Array::new_strided](../struct.Array.html#method.new_strided) keep
+//! their strides when serialized from the CPU backend, in which case the buffer is written
+//! directly from the memory held by the Array, starting at its first element, without
+//! intermediate copies. For other
+//! backends, or arrays whose layout isn't recorded, the elements are copied to host once and
+//! stored contiguously in column major order.
+//!
+//! Deserialization uploads the buffer, along with the stored strides, directly into a new
+//! device allocation. When the deserializer supports borrowing, as is the case for
+//! `bincode::deserialize` from a slice, the buffer isn't copied on host either.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use arrayfire::{randu, Array, Dim4};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Checkpoint {
+//!     step: u64,
+//!     #[serde(with = "arrayfire::array_bytes")]
+//!     weights: Array<f32>,
+//! }
+//!
+//! let checkpoint = Checkpoint {
+//!     step: 10,
+//!     weights: randu::<f32>(Dim4::new(&[128, 128, 1, 1])),
+//! };
+//! let encoded = bincode::serialize(&checkpoint).unwrap();
+//! let decoded: Checkpoint = bincode::deserialize(&encoded).unwrap();
+//! assert_eq!(decoded.weights.dims(), checkpoint.weights.dims());
+//! ```
+
+use super::array::Array;
+use super::defines::{AfError, Backend, DType};
+use super::device::sync;
+use super::dim4::Dim4;
+use super::error::HANDLE_ERROR;
+use super::util::{af_array, dim_t, void_ptr, HasAfEnum};
+
+use libc::{c_int, c_longlong, c_uint, c_void};
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
+use std::marker::PhantomData;
+
+extern "C" {
+    fn af_get_data_ptr(data: *mut c_void, arr: af_array) -> c_int;
+
+    fn af_get_raw_ptr(ptr: *mut void_ptr, arr: af_array) -> c_int;
+
+    fn af_create_strided_array(
+        arr: *mut af_array,
+        data: *const c_void,
+        offset: dim_t,
+        ndims: c_uint,
+        dims: *const dim_t,
+        strides: *const dim_t,
+        aftype: c_uint,
+        stype: c_uint,
+    ) -> c_int;
+}
+
+const FIELDS: &[&str] = &["dtype", "shape", "strides", "offset", "data"];
+
+/// Strides of an Array of dimensions `dims` with elements stored contiguously
+fn contiguous_strides(dims: Dim4) -> Dim4 {
+    Dim4::new(&[1, dims[0], dims[0] * dims[1], dims[0] * dims[1] * dims[2]])
+}
+
+/// Number of elements, starting from the first element, covered by the given layout
+///
+/// Returns None if the span doesn't fit in `usize`.
+fn layout_span(dims: Dim4, strides: Dim4) -> Option<usize> {
+    if dims.get().contains(&0) {
+        return Some(0);
+    }
+    let last = (0..4).try_fold(0u64, |acc, i| {
+        (dims[i] - 1)
+            .checked_mul(strides[i])
+            .and_then(|extent| acc.checked_add(extent))
+    })?;
+    usize::try_from(last.checked_add(1)?).ok()
+}
+
+/// Size in bytes of each part that is stored in little endian order
+fn swap_unit(dtype: DType) -> usize {
+    match dtype {
+        DType::C32 => 4,
+        DType::C64 => 8,
+        DType::F64 | DType::S64 | DType::U64 => 8,
+        DType::F32 | DType::S32 | DType::U32 => 4,
+        DType::S16 | DType::U16 | DType::F16 => 2,
+        DType::B8 | DType::U8 => 1,
+    }
+}
+
+/// Converts between native and little endian byte order
+fn to_little_endian(bytes: &mut [u8], dtype: DType) {
+    if cfg!(target_endian = "big") {
+        for value in bytes.chunks_exact_mut(swap_unit(dtype)) {
+            value.reverse();
+        }
+    }
+}
+
+/// Wrapper that serializes the data buffer using `serialize_bytes`
+struct Bytes<'a>(&'a [u8]);
+
+impl Serialize for Bytes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+/// Serialize `array` in the byte oriented format
+pub fn serialize<T, S>(array: &Array<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: HasAfEnum,
+    S: Serializer,
+{
+    let dtype = T::get_af_dtype();
+    let size = std::mem::size_of::<T>();
+    let dims = array.dims();
+
+    let raw = if array.get_backend() == Backend::CPU {
+        // Memory of CPU backend is host memory, read it in place once pending work is done
+        array.eval();
+        sync(array.get_device_id());
+        let mut ptr: void_ptr = std::ptr::null_mut();
+        let err_val = unsafe { af_get_raw_ptr(&mut ptr as *mut void_ptr, array.get()) };
+        HANDLE_ERROR(AfError::from(err_val));
+        let strides = array.strides();
+        let bytes: Option<&[u8]> = match layout_span(dims, strides) {
+            Some(0) => Some(&[]),
+            Some(span) if !ptr.is_null() => {
+                // Raw pointer is the start of the buffer, which a sub-array shares with its
+                // parent. Only the elements from the offset of the view onwards are written.
+                let start = unsafe { (ptr as *const u8).add(array.offset() as usize * size) };
+                Some(unsafe { std::slice::from_raw_parts(start, span * size) })
+            }
+            _ => None,
+        };
+        bytes.map(|bytes| (strides, 0, Cow::Borrowed(bytes)))
+    } else {
+        None
+    };
+    let (strides, offset, mut data) = match raw {
+        Some(raw) => raw,
+        None => {
+            let mut bytes = vec![0u8; array.elements() * size];
+            // Buffer has room for all elements, alignment is irrelevant for the copy
+            let err_val =
+                unsafe { af_get_data_ptr(bytes.as_mut_ptr() as *mut c_void, array.get()) };
+            HANDLE_ERROR(AfError::from(err_val));
+            (contiguous_strides(dims), 0, Cow::Owned(bytes))
+        }
+    };
+    if cfg!(target_endian = "big") {
+        to_little_endian(data.to_mut(), dtype);
+    }
+
+    let mut state = serializer.serialize_struct("Array", FIELDS.len())?;
+    state.serialize_field("dtype", &dtype)?;
+    state.serialize_field("shape", &dims)?;
+    state.serialize_field("strides", &strides)?;
+    state.serialize_field("offset", &offset)?;
+    state.serialize_field("data", &Bytes(&data))?;
+    state.end()
+}
+
+/// Layout information preceding the data buffer
+struct Layout<T> {
+    shape: Dim4,
+    strides: Dim4,
+    offset: i64,
+    phantom: PhantomData<T>,
+}
+
+impl<T: HasAfEnum> Layout<T> {
+    fn check_dtype<E: de::Error>(dtype: DType) -> Result<(), E> {
+        let expected = T::get_af_dtype();
+        if dtype == expected {
+            Ok(())
+        } else {
+            Err(E::custom(format!(
+                "stored data type is {:?}, requested type is {:?}",
+                dtype, expected
+            )))
+        }
+    }
+
+    /// Uploads `bytes` holding little endian elements to a new device allocation
+    fn build<E: de::Error>(&self, bytes: &[u8]) -> Result<Array<T>, E> {
+        let size = std::mem::size_of::<T>();
+        let offset = usize::try_from(self.offset)
+            .map_err(|_| E::custom(format!("invalid data offset {}", self.offset)))?;
+        let span = layout_span(self.shape, self.strides);
+        if span != Some(0) && (0..4).any(|i| self.shape[i] > 1 && self.strides[i] == 0) {
+            return Err(E::custom(format!("invalid strides {}", self.strides)));
+        }
+        let expected = match span {
+            Some(0) => Some(0),
+            span => span
+                .and_then(|span| span.checked_add(offset))
+                .and_then(|span| span.checked_mul(size)),
+        }
+        .ok_or_else(|| {
+            E::custom(format!(
+                "layout of shape {} and strides {} is too large",
+                self.shape, self.strides
+            ))
+        })?;
+        if bytes.len() != expected {
+            return Err(E::invalid_length(
+                bytes.len(),
+                &format!("{} bytes of data", expected).as_str(),
+            ));
+        }
+        let swapped;
+        let bytes = if cfg!(target_endian = "big") {
+            let mut owned = bytes.to_vec();
+            to_little_endian(&mut owned, T::get_af_dtype());
+            swapped = owned;
+            &swapped[..]
+        } else {
+            bytes
+        };
+
+        let mut temp: af_array = std::ptr::null_mut();
+        let err_val = unsafe {
+            af_create_strided_array(
+                &mut temp as *mut af_array,
+                bytes.as_ptr() as *const c_void,
+                self.offset as dim_t,
+                4,
+                self.shape.get().as_ptr() as *const c_longlong,
+                self.strides.get().as_ptr() as *const c_longlong,
+                T::get_af_dtype() as c_uint,
+                1_u32,
+            )
+        };
+        HANDLE_ERROR(AfError::from(err_val));
+        Ok(temp.into())
+    }
+}
+
+impl<'de, T: HasAfEnum> DeserializeSeed<'de> for Layout<T> {
+    type Value = Array<T>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_bytes(self)
+    }
+}
+
+impl<'de, T: HasAfEnum> Visitor<'de> for Layout<T> {
+    type Value = Array<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("array data as bytes")
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        self.build(bytes)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element::<u8>()? {
+            bytes.push(byte);
+        }
+        self.build(&bytes)
+    }
+}
+
+/// Owned data buffer for formats whose fields may arrive in any order
+struct ByteBuf(Vec<u8>);
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ByteBufVisitor;
+
+        impl<'de> Visitor<'de> for ByteBufVisitor {
+            type Value = ByteBuf;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("array data as bytes")
+            }
+
+            fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+                Ok(ByteBuf(bytes.to_vec()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, bytes: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(ByteBuf(bytes))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    bytes.push(byte);
+                }
+                Ok(ByteBuf(bytes))
+            }
+        }
+
+        deserializer.deserialize_bytes(ByteBufVisitor)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum Field {
+    Dtype,
+    Shape,
+    Strides,
+    Offset,
+    Data,
+}
+
+struct ArrayVisitor<T>(PhantomData<T>);
+
+impl<'de, T: HasAfEnum> Visitor<'de> for ArrayVisitor<T> {
+    type Value = Array<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("struct Array")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let missing = |index| de::Error::invalid_length(index, &self);
+        let dtype: DType = seq.next_element()?.ok_or_else(|| missing(0))?;
+        Layout::<T>::check_dtype::<A::Error>(dtype)?;
+        let layout = Layout {
+            shape: seq.next_element()?.ok_or_else(|| missing(1))?,
+            strides: seq.next_element()?.ok_or_else(|| missing(2))?,
+            offset: seq.next_element()?.ok_or_else(|| missing(3))?,
+            phantom: PhantomData,
+        };
+        // Metadata is known at this point, hence data is uploaded as soon as it's read
+        seq.next_element_seed(layout)?.ok_or_else(|| missing(4))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut dtype: Option<DType> = None;
+        let mut shape = None;
+        let mut strides = None;
+        let mut offset = None;
+        let mut data: Option<ByteBuf> = None;
+        while let Some(key) = map.next_key()? {
+            match key {
+                Field::Dtype => dtype = Some(map.next_value()?),
+                Field::Shape => shape = Some(map.next_value()?),
+                Field::Strides => strides = Some(map.next_value()?),
+                Field::Offset => offset = Some(map.next_value()?),
+                Field::Data => data = Some(map.next_value()?),
+            }
+        }
+        Layout::<T>::check_dtype::<A::Error>(
+            dtype.ok_or_else(|| de::Error::missing_field("dtype"))?,
+        )?;
+        let layout = Layout::<T> {
+            shape: shape.ok_or_else(|| de::Error::missing_field("shape"))?,
+            strides: strides.ok_or_else(|| de::Error::missing_field("strides"))?,
+            offset: offset.ok_or_else(|| de::Error::missing_field("offset"))?,
+            phantom: PhantomData,
+        };
+        let data = data.ok_or_else(|| de::Error::missing_field("data"))?;
+        layout.build(&data.0)
+    }
+}
+
+/// Deserialize an Array stored in the byte oriented format
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Array<T>, D::Error>
+where
+    T: HasAfEnum,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_struct("Array", FIELDS, ArrayVisitor(PhantomData))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::array::Array;
+    use super::super::device::set_device;
+    use crate::dim4;
+    use num::Complex;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper<T: crate::HasAfEnum> {
+        #[serde(with = "super")]
+        array: Array<T>,
+    }
+
+    fn to_vec<T: crate::HasAfEnum>(array: &Array<T>) -> Vec<T> {
+        let mut data = vec![T::default(); array.elements()];
+        array.host(&mut data);
+        data
+    }
+
+    #[test]
+    fn array_bytes_bincode_and_json() {
+        set_device(0);
+        let values: Vec<Complex<f32>> = (0..6).map(|v| Complex::new(v as f32, 1.0)).collect();
+        let input = Wrapper {
+            array: Array::new(&values, dim4!(3, 2)),
+        };
+
+        let encoded = bincode::serialize(&input).unwrap();
+        let decoded: Wrapper<Complex<f32>> = bincode::deserialize(&encoded).unwrap();
+        assert_eq!(decoded.array.dims(), dim4!(3, 2));
+        assert_eq!(to_vec(&decoded.array), values);
+
+        let json = serde_json::to_string(&input).unwrap();
+        let decoded: Wrapper<Complex<f32>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(to_vec(&decoded.array), values);
+
+        assert!(bincode::deserialize::<Wrapper<f64>>(&encoded).is_err());
+    }
+
+    #[test]
+    fn array_bytes_strided() {
+        set_device(0);
+        let buffer: Vec<i32> = (0..20).collect();
+        // Second and third rows of a 5x4 matrix
+        let input = Wrapper {
+            array: Array::new_strided(&buffer, 1, dim4!(2, 4), dim4!(1, 5)),
+        };
+        let expected = to_vec(&input.array);
+
+        let encoded = bincode::serialize(&input).unwrap();
+        let decoded: Wrapper<i32> = bincode::deserialize(&encoded).unwrap();
+        assert_eq!(decoded.array.dims(), dim4!(2, 4));
+        assert_eq!(to_vec(&decoded.array), expected);
+    }
+
+    #[test]
+    fn array_bytes_rejects_invalid_layout() {
+        set_device(0);
+        let input = Wrapper {
+            array: Array::new(&[1.0f32, 2.0, 3.0, 4.0], dim4!(2, 2)),
+        };
+        let valid = serde_json::to_value(&input).unwrap();
+
+        let mut negative_offset = valid.clone();
+        negative_offset["array"]["offset"] = serde_json::json!(-1);
+        assert!(serde_json::from_value::<Wrapper<f32>>(negative_offset).is_err());
+
+        let mut huge_strides = valid.clone();
+        huge_strides["array"]["strides"] = serde_json::to_value(dim4!(1, u64::MAX / 2)).unwrap();
+        assert!(serde_json::from_value::<Wrapper<f32>>(huge_strides).is_err());
+
+        let mut zero_strides = valid;
+        zero_strides["array"]["strides"] = serde_json::to_value(dim4!(1, 0)).unwrap();
+        assert!(serde_json::from_value::<Wrapper<f32>>(zero_strides).is_err());
+    }
+}
@@ -0,0 +1,270 @@
+use super::array::Array;
+use super::defines::AfError;
+use super::error::{AfResult, ArrayFireError};
+use super::util::{af_array, HasAfEnum};
+
+use libc::{c_char, c_int, c_uint};
+use std::convert::{TryFrom, TryInto};
+use std::ffi::CString;
+use std::fs;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+extern "C" {
+    fn af_save_array(
+        index: *mut c_int,
+        key: *const c_char,
+        arr: af_array,
+        filename: *const c_char,
+        append: bool,
+    ) -> c_int;
+
+    fn af_read_array_index(out: *mut af_array, filename: *const c_char, index: c_uint) -> c_int;
+
+    fn af_read_array_key(out: *mut af_array, filename: *const c_char, key: *const c_char) -> c_int;
+
+    fn af_read_array_key_check(
+        index: *mut c_int,
+        filename: *const c_char,
+        key: *const c_char,
+    ) -> c_int;
+}
+
+fn check(err_val: c_int) -> AfResult<()> {
+    match AfError::from(err_val) {
+        AfError::SUCCESS => Ok(()),
+        err => Err(ArrayFireError::new(err)),
+    }
+}
+
+fn c_string(value: &str) -> AfResult<CString> {
+    CString::new(value).map_err(|_| {
+        ArrayFireError::with_message(AfError::ERR_ARG, "string contains an interior nul byte")
+    })
+}
+
+fn format_error() -> ArrayFireError {
+    ArrayFireError::with_message(AfError::ERR_ARG, "not a valid ArrayFire array file")
+}
+
+/// Checks that `handle`, read from a file, holds elements of type `T`
+fn typed_array<T: HasAfEnum>(handle: af_array) -> AfResult<Array<T>> {
+    let array: Array<T> = handle.into();
+    let stored = array.get_type();
+    let expected = T::get_af_dtype();
+    if stored == expected {
+        Ok(array)
+    } else {
+        Err(ArrayFireError::with_message(
+            AfError::ERR_TYPE,
+            format!(
+                "stored data type is {:?}, requested type is {:?}",
+                stored, expected
+            ),
+        ))
+    }
+}
+
+/// File holding named arrays in ArrayFire's native format
+///
+/// Each array stored in the file is identified by a key as well as the position at which it was
+/// added. The files can be read by ArrayFire applications written in any language.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use arrayfire::{randu, ArrayFile, Dim4};
+///
+/// let file = ArrayFile::new("data.af");
+/// file.save("weights", &randu::<f32>(Dim4::new(&[3, 4, 1, 1]))).unwrap();
+/// file.append("labels", &randu::<u8>(Dim4::new(&[4, 1, 1, 1]))).unwrap();
+///
+/// assert_eq!(file.keys().unwrap(), vec!["weights", "labels"]);
+/// let weights = file.read::<f32>("weights").unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct ArrayFile {
+    path: PathBuf,
+}
+
+impl ArrayFile {
+    /// Create an object referring to the file at `path`
+    ///
+    /// The file is not accessed until arrays are saved or read.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Returns the path of the file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn c_path(&self) -> AfResult<CString> {
+        match self.path.to_str() {
+            Some(path) => c_string(path),
+            None => Err(ArrayFireError::with_message(
+                AfError::ERR_ARG,
+                "file path is not valid unicode",
+            )),
+        }
+    }
+
+    fn store<T: HasAfEnum>(&self, key: &str, array: &Array<T>, append: bool) -> AfResult<u32> {
+        let key = c_string(key)?;
+        let path = self.c_path()?;
+        let mut index: c_int = 0;
+        check(unsafe {
+            af_save_array(
+                &mut index as *mut c_int,
+                key.as_ptr(),
+                array.get(),
+                path.as_ptr(),
+                append,
+            )
+        })?;
+        Ok(index as u32)
+    }
+
+    /// Write `array` under `key` as the only array in the file
+    ///
+    /// Any existing contents of the file are discarded. Returns the index of the array,
+    /// which is always zero.
+    pub fn save<T: HasAfEnum>(&self, key: &str, array: &Array<T>) -> AfResult<u32> {
+        self.store(key, array, false)
+    }
+
+    /// Add `array` under `key` to the arrays already in the file
+    ///
+    /// The file is created if it doesn't exist. Returns the index of the array in the file.
+    pub fn append<T: HasAfEnum>(&self, key: &str, array: &Array<T>) -> AfResult<u32> {
+        self.store(key, array, true)
+    }
+
+    /// Returns the index of the array stored under `key`, if any
+    pub fn index_of(&self, key: &str) -> AfResult<Option<u32>> {
+        let key = c_string(key)?;
+        let path = self.c_path()?;
+        let mut index: c_int = -1;
+        check(unsafe {
+            af_read_array_key_check(&mut index as *mut c_int, path.as_ptr(), key.as_ptr())
+        })?;
+        Ok(if index < 0 { None } else { Some(index as u32) })
+    }
+
+    /// Returns the keys of all arrays in the file, in the order of their indices
+    pub fn keys(&self) -> AfResult<Vec<String>> {
+        let file = fs::File::open(&self.path)
+            .map_err(|err| ArrayFireError::with_message(AfError::ERR_ARG, format!("{}", err)))?;
+        read_keys(&mut BufReader::new(file)).ok_or_else(format_error)
+    }
+
+    /// Read the array stored under `key`
+    ///
+    /// Fails if no array is stored under `key` or the stored data type doesn't
+    /// correspond to `T`.
+    pub fn read<T: HasAfEnum>(&self, key: &str) -> AfResult<Array<T>> {
+        let key_str = c_string(key)?;
+        let path = self.c_path()?;
+        if self.index_of(key)?.is_none() {
+            return Err(ArrayFireError::with_message(
+                AfError::ERR_ARG,
+                format!("no array stored under key '{}'", key),
+            ));
+        }
+        let mut temp: af_array = std::ptr::null_mut();
+        check(unsafe {
+            af_read_array_key(&mut temp as *mut af_array, path.as_ptr(), key_str.as_ptr())
+        })?;
+        typed_array(temp)
+    }
+
+    /// Read the array stored at position `index`
+    ///
+    /// Fails if `index` is out of bounds or the stored data type doesn't correspond to `T`.
+    pub fn read_index<T: HasAfEnum>(&self, index: u32) -> AfResult<Array<T>> {
+        let path = self.c_path()?;
+        let mut temp: af_array = std::ptr::null_mut();
+        check(unsafe {
+            af_read_array_index(&mut temp as *mut af_array, path.as_ptr(), index as c_uint)
+        })?;
+        typed_array(temp)
+    }
+}
+
+/// Reads array keys from the file header
+///
+/// The header consists of a one byte version and a four byte array count, followed by
+/// a four byte key length, the key and an eight byte data offset for each array. Array
+/// data follows the header and is not read.
+fn read_keys<R: Read>(reader: &mut R) -> Option<Vec<String>> {
+    fn take<R: Read>(reader: &mut R, len: usize) -> Option<Vec<u8>> {
+        let mut bytes = Vec::new();
+        reader.take(len as u64).read_to_end(&mut bytes).ok()?;
+        if bytes.len() == len {
+            Some(bytes)
+        } else {
+            None
+        }
+    }
+    fn take_int<R: Read>(reader: &mut R) -> Option<c_int> {
+        Some(c_int::from_ne_bytes(take(reader, 4)?.try_into().ok()?))
+    }
+
+    take(reader, 1)?;
+    let count = take_int(reader)?;
+    let mut keys = Vec::new();
+    for _ in 0..count {
+        let len = usize::try_from(take_int(reader)?).ok()?;
+        keys.push(String::from_utf8_lossy(&take(reader, len)?).into_owned());
+        take(reader, 8)?;
+    }
+    Some(keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::array::Array;
+    use super::super::defines::AfError;
+    use super::super::device::set_device;
+    use super::ArrayFile;
+    use crate::dim4;
+
+    #[test]
+    fn array_file_roundtrip() {
+        set_device(0);
+        let path = std::env::temp_dir().join(format!(
+            "arrayfire_rust_{}_array_file_roundtrip.af",
+            std::process::id()
+        ));
+        let file = ArrayFile::new(&path);
+
+        let values = Array::new(&[1.0f32, 2.0, 3.0, 4.0], dim4!(2, 2));
+        let flags = Array::new(&[1u8, 0, 1], dim4!(3));
+        assert_eq!(file.save("values", &values).unwrap(), 0);
+        assert_eq!(file.append("flags", &flags).unwrap(), 1);
+
+        assert_eq!(file.keys().unwrap(), vec!["values", "flags"]);
+        assert_eq!(file.index_of("flags").unwrap(), Some(1));
+        assert_eq!(file.index_of("missing").unwrap(), None);
+
+        let read = file.read::<f32>("values").unwrap();
+        let mut host = vec![0.0f32; 4];
+        read.host(&mut host);
+        assert_eq!(host, vec![1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(file.read_index::<u8>(1).unwrap().dims(), dim4!(3));
+
+        assert_eq!(
+            file.read::<f64>("values").unwrap_err().code(),
+            AfError::ERR_TYPE
+        );
+        assert_eq!(
+            file.read::<f32>("missing").unwrap_err().code(),
+            AfError::ERR_ARG
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
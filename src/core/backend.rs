@@ -1,5 +1,5 @@
 use super::defines::{AfError, Backend};
-use super::error::HANDLE_ERROR;
+use super::error::{af_result, AfResult, HANDLE_ERROR};
 
 use libc::{c_int, c_uint};
 
@@ -8,6 +8,7 @@ extern "C" {
     fn af_get_backend_count(num_backends: *mut c_uint) -> c_int;
     fn af_get_available_backends(backends: *mut c_int) -> c_int;
     fn af_get_active_backend(backend: *mut c_int) -> c_int;
+    fn af_set_device(device: c_int) -> c_int;
 }
 
 /// Toggle backends between cuda, opencl or cpu
@@ -20,6 +21,15 @@ pub fn set_backend(backend: Backend) {
     HANDLE_ERROR(AfError::from(err_val));
 }
 
+/// Make `backend` and its device `device` active on the calling thread
+///
+/// Threads spawned by the crate start on the default backend, this is used to run their
+/// ArrayFire calls on the backend and device of the thread that spawned them.
+pub(crate) fn activate(backend: Backend, device: i32) -> AfResult<()> {
+    af_result(AfError::from(unsafe { af_set_backend(backend as u8) }))?;
+    af_result(AfError::from(unsafe { af_set_device(device) }))
+}
+
 /// Get the available backend count
 pub fn get_backend_count() -> u32 {
     let mut temp: u32 = 0;
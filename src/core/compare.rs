@@ -0,0 +1,349 @@
+use super::arith::{abs, add, and, eq, isnan, le, mul, or, sub};
+use super::array::Array;
+use super::data::{flat, ConstGenerator};
+use super::defines::DType;
+use super::dim4::Dim4;
+use super::index::index;
+use super::seq::Seq;
+use super::util::{Fromf64, HasAfEnum, ImplicitPromote};
+use crate::algorithm::all_true_all;
+
+use std::fmt;
+
+fn is_complex(dtype: DType) -> bool {
+    dtype == DType::C32 || dtype == DType::C64
+}
+
+/// Element wise comparison of two Arrays of identical dimensions
+struct Closeness<W: HasAfEnum> {
+    /// `|lhs - rhs|`
+    diff: Array<W>,
+    /// `atol + rtol * |rhs|`
+    tol: Array<W>,
+    /// Elements considered close
+    close: Array<bool>,
+}
+
+impl<W: HasAfEnum> Closeness<W> {
+    fn all_close(&self) -> bool {
+        all_true_all(&self.close).0
+    }
+}
+
+/// Compare `lhs` and `rhs` in `V`, whose magnitudes are of type `W`
+fn compare<V, W>(
+    lhs: &Array<V>,
+    rhs: &Array<V>,
+    rtol: f64,
+    atol: f64,
+    equal_nan: bool,
+) -> Closeness<W>
+where
+    V: HasAfEnum<AbsOutType = W>,
+    W: HasAfEnum + ConstGenerator<OutType = W> + Fromf64,
+{
+    let diff = abs(&sub(lhs, rhs, false));
+    let rmag = abs(rhs);
+    let tol = add(
+        &W::fromf64(atol),
+        &mul(&W::fromf64(rtol), &rmag, false),
+        false,
+    );
+
+    // Infinities of the same sign are close although their difference is NaN
+    let mut close = or(&le(&diff, &tol, false), &eq(lhs, rhs, false), false);
+    if equal_nan {
+        let both_nan = and(&isnan(&abs(lhs)), &isnan(&rmag), false);
+        close = or(&close, &both_nan, false);
+    }
+    Closeness { diff, tol, close }
+}
+
+fn closeness<T>(
+    lhs: &Array<T>,
+    rhs: &Array<T>,
+    rtol: f64,
+    atol: f64,
+    equal_nan: bool,
+) -> Closeness<T::AbsOutType>
+where
+    T: HasAfEnum,
+    T::AbsOutType:
+        HasAfEnum<AbsOutType = T::AbsOutType> + ConstGenerator<OutType = T::AbsOutType> + Fromf64,
+{
+    if is_complex(lhs.get_type()) {
+        compare(lhs, rhs, rtol, atol, equal_nan)
+    } else {
+        // Real inputs are compared in the floating point type of their magnitude, which keeps
+        // differences of unsigned integers from wrapping around
+        let lw = lhs.cast::<T::AbsOutType>();
+        let rw = rhs.cast::<T::AbsOutType>();
+        compare(&lw, &rw, rtol, atol, equal_nan)
+    }
+}
+
+impl<T> Array<T>
+where
+    T: HasAfEnum,
+    T::AbsOutType:
+        HasAfEnum<AbsOutType = T::AbsOutType> + ConstGenerator<OutType = T::AbsOutType> + Fromf64,
+{
+    /// Check if all elements of two Arrays are approximately equal
+    ///
+    /// Elements `a` of `self` and `b` of `other` are considered close if
+    /// `|a - b| <= atol + rtol * |b|`, or if they are equal. NaN values are considered equal to
+    /// each other only if `equal_nan` is true. The comparison is done on the device in the
+    /// precision of `T::AbsOutType`.
+    ///
+    /// # Return Values
+    ///
+    /// True if the Arrays have the same dimensions and all elements are close.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arrayfire::{Array, Dim4};
+    /// let dims = Dim4::new(&[3, 1, 1, 1]);
+    /// let a = Array::new(&[1.0f32, 2.0, f32::NAN], dims);
+    /// let b = Array::new(&[1.0f32, 2.000001, f32::NAN], dims);
+    /// assert!(a.allclose(&b, 1e-5, 1e-8, true));
+    /// assert!(!a.allclose(&b, 1e-5, 1e-8, false));
+    /// ```
+    pub fn allclose(&self, other: &Array<T>, rtol: f64, atol: f64, equal_nan: bool) -> bool {
+        if self.dims() != other.dims() {
+            return false;
+        }
+        if self.elements() == 0 {
+            return true;
+        }
+        closeness(self, other, rtol, atol, equal_nan).all_close()
+    }
+}
+
+/// Check if two Arrays have the same dimensions, data type and elements
+///
+/// Unlike [allclose](./struct.Array.html#method.allclose), elements have to be exactly equal and
+/// NaN values are never equal.
+///
+/// # Examples
+///
+/// ```rust
+/// use arrayfire::{array_equal, Array, Dim4};
+/// let a = Array::new(&[1, 2, 3, 4], Dim4::new(&[2, 2, 1, 1]));
+/// assert!(array_equal(&a, &a.copy()));
+/// assert!(!array_equal(&a, &Array::new(&[1, 2, 3, 4], Dim4::new(&[4, 1, 1, 1]))));
+/// assert!(!array_equal(&a, &a.cast::<f32>()));
+/// ```
+pub fn array_equal<A, B>(lhs: &Array<A>, rhs: &Array<B>) -> bool
+where
+    A: ImplicitPromote<B>,
+    B: ImplicitPromote<A>,
+{
+    if lhs.dims() != rhs.dims() || lhs.get_type() != rhs.get_type() {
+        return false;
+    }
+    if lhs.elements() == 0 {
+        return true;
+    }
+    all_true_all(&eq(lhs, rhs, false)).0
+}
+
+/// Description of the largest difference between two Arrays that are not close
+///
+/// Returned by [close_mismatch](./fn.close_mismatch.html), and printed by
+/// [assert_array_close!](./macro.assert_array_close.html) on failure.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CloseMismatch {
+    /// The Arrays have different dimensions
+    Dims(Dim4, Dim4),
+    /// Elements at `position` differ by more than the tolerance
+    Values {
+        /// Position of the worst offending element
+        position: [u64; 4],
+        /// Value of the left hand side Array
+        lhs: String,
+        /// Value of the right hand side Array
+        rhs: String,
+        /// Absolute difference of the values
+        diff: f64,
+        /// Tolerance of the element
+        tol: f64,
+        /// Number of elements that are not close
+        count: usize,
+    },
+}
+
+impl fmt::Display for CloseMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CloseMismatch::Dims(lhs, rhs) => write!(f, "dimensions differ: {} vs {}", lhs, rhs),
+            CloseMismatch::Values {
+                position,
+                lhs,
+                rhs,
+                diff,
+                tol,
+                count,
+            } => write!(
+                f,
+                "{} element(s) differ, worst at {:?}: left = {}, right = {}, |left - right| = {} > {}",
+                count, position, lhs, rhs, diff, tol
+            ),
+        }
+    }
+}
+
+fn to_f64<W: HasAfEnum>(array: &Array<W>) -> Vec<f64> {
+    match array.get_type() {
+        DType::F64 => {
+            let mut values = vec![0.0f64; array.elements()];
+            array.host(&mut values);
+            values
+        }
+        _ => {
+            let mut values = vec![0.0f32; array.elements()];
+            array.cast::<f32>().host(&mut values);
+            values.into_iter().map(f64::from).collect()
+        }
+    }
+}
+
+/// Debug representation of element `at` of `input`, fetching only that element from the device
+fn element_repr<T: HasAfEnum + fmt::Debug>(input: &Array<T>, at: usize) -> String {
+    let at = at as f64;
+    let element = index(&flat(input), &[Seq::new(at, at, 1.0)]);
+    match element.scalar() {
+        Ok(value) => format!("{:?}", value),
+        Err(err) => err.to_string(),
+    }
+}
+
+/// Find the worst offending elements of two Arrays that are not close
+///
+/// Elements are compared as in [Array::allclose](./struct.Array.html#method.allclose).
+///
+/// # Return Values
+///
+/// None if the Arrays are close, otherwise the mismatch with the largest excess of the
+/// difference over the tolerance. NaN differences count as the largest excess.
+pub fn close_mismatch<T>(
+    lhs: &Array<T>,
+    rhs: &Array<T>,
+    rtol: f64,
+    atol: f64,
+    equal_nan: bool,
+) -> Option<CloseMismatch>
+where
+    T: HasAfEnum + fmt::Debug,
+    T::AbsOutType:
+        HasAfEnum<AbsOutType = T::AbsOutType> + ConstGenerator<OutType = T::AbsOutType> + Fromf64,
+{
+    if lhs.dims() != rhs.dims() {
+        return Some(CloseMismatch::Dims(lhs.dims(), rhs.dims()));
+    }
+    if lhs.elements() == 0 {
+        return None;
+    }
+    let result = closeness(lhs, rhs, rtol, atol, equal_nan);
+    if result.all_close() {
+        return None;
+    }
+
+    let diff = to_f64(&result.diff);
+    let tol = to_f64(&result.tol);
+    let mut close = vec![false; lhs.elements()];
+    result.close.host(&mut close);
+
+    let excess = |i: usize| {
+        let e = diff[i] - tol[i];
+        if e.is_nan() {
+            f64::INFINITY
+        } else {
+            e
+        }
+    };
+    let mismatches: Vec<usize> = (0..close.len()).filter(|&i| !close[i]).collect();
+    let worst = *mismatches
+        .iter()
+        .max_by(|&&a, &&b| excess(a).partial_cmp(&excess(b)).unwrap())?;
+
+    let dims = lhs.dims();
+    let mut position = [0u64; 4];
+    let mut rest = worst as u64;
+    for (axis, pos) in position.iter_mut().enumerate() {
+        *pos = rest % dims[axis];
+        rest /= dims[axis];
+    }
+    Some(CloseMismatch::Values {
+        position,
+        lhs: element_repr(lhs, worst),
+        rhs: element_repr(rhs, worst),
+        diff: diff[worst],
+        tol: tol[worst],
+        count: mismatches.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::array::Array;
+    use super::super::device::set_device;
+    use super::{array_equal, close_mismatch, CloseMismatch};
+    use crate::dim4;
+    use num::Complex;
+
+    #[test]
+    fn allclose_and_array_equal() {
+        set_device(0);
+        let a = Array::new(&[1.0f64, 2.0, f64::INFINITY, f64::NAN], dim4!(2, 2));
+        let b = Array::new(&[1.0f64, 2.0 + 1e-9, f64::INFINITY, f64::NAN], dim4!(2, 2));
+        assert!(a.allclose(&b, 1e-6, 0.0, true));
+        assert!(!a.allclose(&b, 1e-6, 0.0, false));
+        assert!(!a.allclose(&b, 0.0, 0.0, true));
+        assert!(!a.allclose(&Array::new(&[1.0f64, 2.0], dim4!(2)), 1.0, 1.0, true));
+
+        let u = Array::new(&[1u32, 5], dim4!(2));
+        let v = Array::new(&[2u32, 5], dim4!(2));
+        assert!(u.allclose(&v, 0.0, 1.0, false));
+        assert!(!u.allclose(&v, 0.0, 0.5, false));
+
+        let z = Array::new(&[Complex::new(1.0f32, 1.0)], dim4!(1));
+        let w = Array::new(&[Complex::new(1.0f32, 1.5)], dim4!(1));
+        assert!(z.allclose(&w, 0.0, 0.5, false));
+        assert!(!z.allclose(&w, 0.0, 0.25, false));
+
+        assert!(array_equal(&u, &u.copy()));
+        assert!(!array_equal(&u, &v));
+        assert!(!array_equal(&u, &u.cast::<i32>()));
+        assert!(!array_equal(&a, &a));
+    }
+
+    #[test]
+    fn close_mismatch_reports_worst_element() {
+        set_device(0);
+        let a = Array::new(&[1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0], dim4!(2, 3));
+        let b = Array::new(&[1.0f32, 2.5, 3.0, 4.0, 5.0, 9.0], dim4!(2, 3));
+        assert_eq!(close_mismatch(&a, &a, 0.0, 0.0, false), None);
+
+        match close_mismatch(&a, &b, 0.0, 0.1, false) {
+            Some(CloseMismatch::Values {
+                position,
+                lhs,
+                rhs,
+                count,
+                ..
+            }) => {
+                assert_eq!(position, [1, 2, 0, 0]);
+                assert_eq!((lhs.as_str(), rhs.as_str()), ("6.0", "9.0"));
+                assert_eq!(count, 2);
+            }
+            mismatch => panic!("unexpected mismatch {:?}", mismatch),
+        }
+
+        let c = Array::new(&[1.0f32, 2.0], dim4!(2));
+        assert_eq!(
+            close_mismatch(&a, &c, 0.0, 0.0, false),
+            Some(CloseMismatch::Dims(dim4!(2, 3), dim4!(2)))
+        );
+    }
+}
@@ -600,6 +600,18 @@ pub enum CublasMathMode {
     DEFAULT = 0,
 }
 
+/// Accumulation precision of batched matrix multiplication for f16 Arrays
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "afserde", derive(Serialize, Deserialize))]
+pub enum HalfAccumulation {
+    /// Multiply and accumulate in f16, subject to [set_cublas_mode](./fn.set_cublas_mode.html)
+    /// on the CUDA backend
+    DEFAULT = 0,
+    /// Promote operands to f32, accumulate in f32 and round the result back to f16
+    F32 = 1,
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(feature = "afserde")]
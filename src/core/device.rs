@@ -1,5 +1,5 @@
 use super::defines::AfError;
-use super::error::HANDLE_ERROR;
+use super::error::{handle_wrapper_error, HANDLE_ERROR};
 use super::util::{dim_t, free_host, void_ptr};
 
 use libc::{c_char, c_int, size_t};
@@ -242,7 +242,7 @@ pub fn print_mem_info(msg: String, device: i32) {
             };
             HANDLE_ERROR(AfError::from(err_val));
         }
-        Err(_) => HANDLE_ERROR(AfError::ERR_INTERNAL),
+        Err(_) => handle_wrapper_error(AfError::ERR_INTERNAL, "message contains a nul byte"),
     }
 }
 
@@ -2,8 +2,12 @@ use super::defines::AfError;
 use super::util::{dim_t, free_host};
 
 use libc::c_char;
+use std::cell::RefCell;
+use std::error::Error;
 use std::ffi::CStr;
+use std::fmt;
 use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
 use std::sync::RwLock;
 
 extern "C" {
@@ -14,14 +18,22 @@ extern "C" {
 pub type ErrorCallback = fn(AfError);
 
 /// Structure holding handle to callback function
+///
+/// The callback can either be a plain function of type [ErrorCallback](./type.ErrorCallback.html)
+/// or a closure that captures state, as long as it can be shared across threads.
 pub struct Callback {
-    cb: ErrorCallback,
+    cb: Box<dyn Fn(AfError) + Send + Sync>,
 }
 
 impl Callback {
     /// Associated function to create a new Callback object
-    pub fn new(callback: ErrorCallback) -> Self {
-        Self { cb: callback }
+    pub fn new<F>(callback: F) -> Self
+    where
+        F: Fn(AfError) + Send + Sync + 'static,
+    {
+        Self {
+            cb: Box::new(callback),
+        }
     }
 
     /// call invokes the error callback with `error_code`.
@@ -30,6 +42,63 @@ impl Callback {
     }
 }
 
+/// Error returned by fallible ArrayFire calls
+///
+/// Holds the error code returned by the ArrayFire C-API along with the description
+/// fetched using [get_last_error](./fn.get_last_error.html) at the time of failure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArrayFireError {
+    code: AfError,
+    message: String,
+}
+
+impl ArrayFireError {
+    /// Create a new error object for `code`, capturing the last error description
+    pub fn new(code: AfError) -> Self {
+        Self {
+            code,
+            message: get_last_error(),
+        }
+    }
+
+    /// Create a new error object for `code` with the given description
+    pub fn with_message<S: Into<String>>(code: AfError, message: S) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Returns the error code
+    pub fn code(&self) -> AfError {
+        self.code
+    }
+
+    /// Returns the error description
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ArrayFireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({:?}): {}", self.code, self.code, self.message)
+    }
+}
+
+impl Error for ArrayFireError {}
+
+impl From<ArrayFireError> for AfError {
+    fn from(err: ArrayFireError) -> Self {
+        err.code
+    }
+}
+
+impl Error for AfError {}
+
+/// Short type alias for results of fallible ArrayFire calls
+pub type AfResult<T> = Result<T, ArrayFireError>;
+
 /// Default error handling callback provided by ArrayFire crate
 pub fn handle_error_general(error_code: AfError) {
     match error_code {
@@ -47,8 +116,166 @@ lazy_static! {
         RwLock::new(Callback::new(handle_error_general));
 }
 
+/// Error handling scope active on a thread
+enum ErrorScope {
+    /// Scope created by `try_af`, holds the first error raised within it
+    Capture(Option<ArrayFireError>),
+    /// Scope created by `with_error_handler`
+    Handler(Rc<Callback>),
+}
+
+thread_local! {
+    /// Error handling scopes of current thread, innermost scope being the last entry.
+    static ERROR_SCOPES: RefCell<Vec<ErrorScope>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Pops the scope pushed onto `ERROR_SCOPES` even if the enclosed closure unwinds
+struct ScopeGuard;
+
+impl ScopeGuard {
+    fn enter(scope: ErrorScope) -> Self {
+        ERROR_SCOPES.with(|scopes| scopes.borrow_mut().push(scope));
+        Self
+    }
+
+    fn exit(self) -> Option<ErrorScope> {
+        let scope = ERROR_SCOPES.with(|scopes| scopes.borrow_mut().pop());
+        std::mem::forget(self);
+        scope
+    }
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        ERROR_SCOPES.with(|scopes| {
+            scopes.borrow_mut().pop();
+        });
+    }
+}
+
+/// Action to be taken by `HANDLE_ERROR` as decided by the innermost scope
+enum ScopeAction {
+    Global,
+    Ignore,
+    Record,
+    Call(Rc<Callback>),
+}
+
+fn scoped_action(error_code: AfError) -> ScopeAction {
+    ERROR_SCOPES.with(|scopes| match scopes.borrow().last() {
+        None => ScopeAction::Global,
+        Some(ErrorScope::Capture(Some(_))) => ScopeAction::Ignore,
+        Some(ErrorScope::Capture(None)) => match error_code {
+            AfError::SUCCESS => ScopeAction::Ignore,
+            _ => ScopeAction::Record,
+        },
+        Some(ErrorScope::Handler(cb)) => ScopeAction::Call(Rc::clone(cb)),
+    })
+}
+
+fn record_error(error_code: AfError, message: Option<&str>) {
+    // Fetching the last error message calls into ArrayFire, hence
+    // no borrow of thread local storage is held at this point.
+    let error = match message {
+        Some(message) => ArrayFireError::with_message(error_code, message),
+        None => ArrayFireError::new(error_code),
+    };
+    ERROR_SCOPES.with(|scopes| {
+        if let Some(ErrorScope::Capture(slot @ None)) = scopes.borrow_mut().last_mut() {
+            *slot = Some(error);
+        }
+    });
+}
+
+/// Run ArrayFire calls and return any failure as an error instead of invoking the error handler
+///
+/// While `f` runs on the current thread, errors returned by ArrayFire FFI calls are recorded
+/// rather than being passed to the registered error handler (which panics by default).
+/// The first error encountered is returned as [ArrayFireError](./struct.ArrayFireError.html).
+/// Calls made after a failure inside the same closure may operate on invalid Arrays, their
+/// errors are ignored and the values they return should not be relied upon.
+///
+/// Calls to `try_af` can be nested, each call reports the errors raised within its own closure.
+/// Errors raised on other threads are not affected.
+///
+/// Most element wise, reduction, convolution and filtering functions also come with a `try_`
+/// prefixed variant, such as [try_add](./fn.try_add.html), that returns the error directly.
+///
+/// # Examples
+///
+/// ```rust
+/// use arrayfire::{dim4, matmul, randu, try_af, AfError, MatProp};
+///
+/// let a = randu::<f32>(dim4!(3, 4));
+/// let b = randu::<f32>(dim4!(5, 2));
+///
+/// let result = try_af(|| matmul(&a, &b, MatProp::NONE, MatProp::NONE));
+/// match result {
+///     Ok(_) => println!("Unexpected success"),
+///     Err(e) => {
+///         assert_eq!(e.code(), AfError::ERR_SIZE);
+///         println!("Failed with: {}", e);
+///     }
+/// }
+/// ```
+pub fn try_af<F, R>(f: F) -> AfResult<R>
+where
+    F: FnOnce() -> R,
+{
+    let guard = ScopeGuard::enter(ErrorScope::Capture(None));
+    let result = f();
+    match guard.exit() {
+        Some(ErrorScope::Capture(Some(error))) => Err(error),
+        _ => Ok(result),
+    }
+}
+
+/// Run ArrayFire calls with an error handler that is active only on the current thread
+///
+/// The handler `cb_value` takes precedence over the handler registered using
+/// [register_error_handler](./fn.register_error_handler.html) for the duration of `f`.
+/// Other threads continue to use their own scoped handlers or the global one. The previous
+/// handler is restored once `f` returns, or unwinds due to a panic.
+///
+/// Scoped handlers and [try_af](./fn.try_af.html) scopes can be nested, the innermost
+/// scope handles the errors.
+///
+/// # Examples
+///
+/// ```rust
+/// use arrayfire::{dim4, matmul, randu, with_error_handler, AfError, Callback, MatProp};
+/// use std::sync::{Arc, Mutex};
+///
+/// let errors = Arc::new(Mutex::new(Vec::new()));
+/// let sink = errors.clone();
+/// let logger = Callback::new(move |code: AfError| {
+///     if code != AfError::SUCCESS {
+///         sink.lock().unwrap().push(code);
+///     }
+/// });
+///
+/// let a = randu::<f32>(dim4!(3, 4));
+/// let b = randu::<f32>(dim4!(5, 2));
+/// with_error_handler(logger, || matmul(&a, &b, MatProp::NONE, MatProp::NONE));
+///
+/// assert_eq!(*errors.lock().unwrap(), vec![AfError::ERR_SIZE]);
+/// ```
+pub fn with_error_handler<F, R>(cb_value: Callback, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let guard = ScopeGuard::enter(ErrorScope::Handler(Rc::new(cb_value)));
+    let result = f();
+    guard.exit();
+    result
+}
+
 /// Register user provided error handler
 ///
+/// The handler registered using this function is shared by all threads. To change the error
+/// handling behavior of the current thread alone, use
+/// [with_error_handler](./fn.with_error_handler.html).
+///
 /// # Examples
 /// ```
 /// #[macro_use]
@@ -84,15 +311,51 @@ pub fn register_error_handler(cb_value: Callback) {
 }
 
 /// Default error handler for error code returned by ArrayFire FFI calls
+///
+/// If the calling thread is inside a [try_af](./fn.try_af.html) or
+/// [with_error_handler](./fn.with_error_handler.html) scope, the innermost scope handles
+/// the error. Otherwise, the error is passed to the globally registered error handler.
 #[allow(non_snake_case)]
-#[allow(clippy::match_wild_err_arm)]
 pub fn HANDLE_ERROR(error_code: AfError) {
-    let gaurd = match ERROR_HANDLER_LOCK.read() {
-        Ok(g) => g,
-        Err(_) => panic!("Failed to acquire lock while handling FFI return value"),
-    };
+    dispatch_error(error_code, None);
+}
+
+/// Report an error detected by the wrapper itself, before any ArrayFire call is made
+///
+/// Errors are handled like in [HANDLE_ERROR](./fn.HANDLE_ERROR.html), except that
+/// [try_af](./fn.try_af.html) scopes record `message` instead of the last error description
+/// of ArrayFire, which would be stale. As the error handler may return, callers have to
+/// return right after reporting the error.
+pub(crate) fn handle_wrapper_error(error_code: AfError, message: &str) {
+    dispatch_error(error_code, Some(message));
+}
 
-    (*gaurd.deref()).call(error_code);
+/// Convert the error code returned by an ArrayFire FFI call into a Result
+///
+/// Unlike [HANDLE_ERROR](./fn.HANDLE_ERROR.html), failures are returned to the caller and
+/// no error handler is invoked.
+pub(crate) fn af_result(error_code: AfError) -> AfResult<()> {
+    match error_code {
+        AfError::SUCCESS => Ok(()),
+        _ => Err(ArrayFireError::new(error_code)),
+    }
+}
+
+#[allow(clippy::match_wild_err_arm)]
+fn dispatch_error(error_code: AfError, message: Option<&str>) {
+    match scoped_action(error_code) {
+        ScopeAction::Global => {
+            let gaurd = match ERROR_HANDLER_LOCK.read() {
+                Ok(g) => g,
+                Err(_) => panic!("Failed to acquire lock while handling FFI return value"),
+            };
+
+            (*gaurd.deref()).call(error_code);
+        }
+        ScopeAction::Ignore => {}
+        ScopeAction::Record => record_error(error_code, message),
+        ScopeAction::Call(cb) => cb.call(error_code),
+    }
 }
 
 /// Fetch last error description as String
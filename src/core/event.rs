@@ -1,9 +1,16 @@
-use super::defines::AfError;
-use super::error::HANDLE_ERROR;
+use super::backend::{activate, get_active_backend};
+use super::defines::{AfError, Backend};
+use super::device::get_device;
+use super::error::{try_af, AfResult, HANDLE_ERROR};
+use super::executor::Completion;
 use super::util::af_event;
 
 use libc::c_int;
 use std::default::Default;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::thread;
 
 extern "C" {
     fn af_create_event(out: *mut af_event) -> c_int;
@@ -66,6 +73,70 @@ impl Event {
     }
 }
 
+impl Event {
+    /// Convert the event into a future that resolves once the event is complete
+    ///
+    /// The event has to be [marked](./struct.Event.html#method.mark) before. ArrayFire has no
+    /// non-blocking query for events, hence the first poll of the future hands the event to a
+    /// helper thread that blocks on it, on the backend and device active while calling this
+    /// method, and wakes the awaiting task once it is complete.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use arrayfire::{randu, Dim4, Event};
+    ///
+    /// async fn compute() {
+    ///     let a = randu::<f32>(Dim4::new(&[512, 512, 1, 1]));
+    ///     let event = Event::default();
+    ///     let b = &a * 2.0f32;
+    ///     event.mark();
+    ///     event.completion().await.unwrap();
+    /// }
+    /// ```
+    pub fn completion(self) -> EventFuture {
+        EventFuture {
+            event: Some(self),
+            backend: get_active_backend(),
+            device: get_device(),
+            completion: Completion::new(),
+        }
+    }
+}
+
+/// Future returned by [Event::completion](./struct.Event.html#method.completion)
+pub struct EventFuture {
+    event: Option<Event>,
+    backend: Backend,
+    device: i32,
+    completion: Completion<()>,
+}
+
+impl EventFuture {
+    /// Check if the event is complete, without blocking
+    ///
+    /// Always false before the future is first polled.
+    pub fn is_ready(&self) -> bool {
+        self.completion.is_complete()
+    }
+}
+
+impl Future for EventFuture {
+    type Output = AfResult<()>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(event) = self.event.take() {
+            let (backend, device) = (self.backend, self.device);
+            let done = self.completion.clone();
+            thread::spawn(move || {
+                let result = activate(backend, device).and_then(|_| try_af(|| event.block()));
+                done.complete(result);
+            });
+        }
+        self.completion.poll_result(cx)
+    }
+}
+
 impl Drop for Event {
     fn drop(&mut self) {
         let ret_val = unsafe { af_delete_event(self.event_handle as af_event) };
@@ -81,6 +152,7 @@ mod tests {
     use super::super::arith::pow;
     use super::super::device::{info, set_device};
     use super::super::event::Event;
+    use super::super::executor::tests::block_on;
     use crate::{af_print, randu};
     use std::sync::mpsc;
     use std::thread;
@@ -152,4 +224,18 @@ mod tests {
 
         // ANCHOR_END: event_block
     }
+
+    #[test]
+    fn event_completion_future() {
+        set_device(0);
+        let a = randu!(10, 10);
+        let event = Event::default();
+        let b = pow(&a, &2.0f32, false);
+        event.mark();
+
+        let future = event.completion();
+        assert!(!future.is_ready());
+        block_on(future).unwrap();
+        af_print!("Squared", &b);
+    }
 }
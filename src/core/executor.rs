@@ -0,0 +1,279 @@
+use super::backend::activate;
+use super::defines::{AfError, Backend};
+use super::error::{try_af, AfResult, ArrayFireError};
+
+use std::any::Any;
+use std::future::Future;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, JoinHandle};
+
+/// Convert the payload of a panic, typically raised by the default error handler, to an error
+fn panic_error(payload: Box<dyn Any + Send>) -> ArrayFireError {
+    let message = match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => String::from(*message),
+            Err(_) => String::from("ArrayFire task panicked"),
+        },
+    };
+    ArrayFireError::with_message(AfError::ERR_INTERNAL, message)
+}
+
+struct State<R> {
+    result: Option<AfResult<R>>,
+    waker: Option<Waker>,
+}
+
+/// Result slot shared between a future and the thread completing it
+pub(crate) struct Completion<R> {
+    state: Arc<Mutex<State<R>>>,
+}
+
+impl<R> Clone for Completion<R> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<R> Completion<R> {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                result: None,
+                waker: None,
+            })),
+        }
+    }
+
+    /// Store `result` and wake the task awaiting it
+    pub(crate) fn complete(&self, result: AfResult<R>) {
+        let waker = {
+            let mut state = self.state.lock().unwrap();
+            state.result = Some(result);
+            state.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    pub(crate) fn is_complete(&self) -> bool {
+        self.state.lock().unwrap().result.is_some()
+    }
+
+    pub(crate) fn poll_result(&self, cx: &mut Context<'_>) -> Poll<AfResult<R>> {
+        let mut state = self.state.lock().unwrap();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Dedicated thread running ArrayFire work for async code
+///
+/// ArrayFire calls block the calling thread, which stalls the reactor of an async runtime. An
+/// AfExecutor owns an OS thread on which the given backend and device are made active once,
+/// and runs closures submitted through [spawn](./struct.AfExecutor.html#method.spawn) on it in
+/// submission order. Each submission returns an [AfTask](./struct.AfTask.html), a future that
+/// resolves to the closure's return value, so no `spawn_blocking` is needed.
+///
+/// Closures run inside [try_af](./fn.try_af.html), so the first error raised by an ArrayFire
+/// call is returned from the task with its original code; the executor keeps running.
+/// Dropping the executor finishes the pending closures and joins its thread.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use arrayfire::{randu, sum_all, AfExecutor, Backend, Dim4};
+///
+/// async fn total() -> f64 {
+///     let executor = AfExecutor::new(Backend::DEFAULT, 0).unwrap();
+///     executor
+///         .spawn(|| sum_all(&randu::<f32>(Dim4::new(&[128, 128, 1, 1]))).0 as f64)
+///         .await
+///         .unwrap()
+/// }
+/// ```
+pub struct AfExecutor {
+    sender: Option<mpsc::Sender<Job>>,
+    thread: Option<JoinHandle<()>>,
+    backend: Backend,
+    device: i32,
+}
+
+impl AfExecutor {
+    /// Start an executor thread running on `device` of `backend`
+    ///
+    /// # Return Values
+    ///
+    /// The executor, or the error raised while activating the backend or device.
+    pub fn new(backend: Backend, device: i32) -> AfResult<Self> {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let (init_sender, init_receiver) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name(format!("arrayfire-{:?}-{}", backend, device))
+            .spawn(move || {
+                let init = activate(backend, device);
+                let failed = init.is_err();
+                let _ = init_sender.send(init);
+                if failed {
+                    return;
+                }
+                for job in receiver {
+                    job();
+                }
+            })
+            .map_err(|err| ArrayFireError::with_message(AfError::ERR_INTERNAL, err.to_string()))?;
+        let init = init_receiver.recv().unwrap_or_else(|_| {
+            Err(ArrayFireError::with_message(
+                AfError::ERR_INTERNAL,
+                String::from("executor thread exited during initialization"),
+            ))
+        });
+        let executor = Self {
+            sender: Some(sender),
+            thread: Some(thread),
+            backend,
+            device,
+        };
+        init.map(|_| executor)
+    }
+
+    /// Backend used by the executor thread
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+
+    /// Device used by the executor thread
+    pub fn device(&self) -> i32 {
+        self.device
+    }
+
+    /// Run `func` on the executor thread
+    ///
+    /// # Return Values
+    ///
+    /// A future resolving to the return value of `func`, to the first error raised by an
+    /// ArrayFire call within `func`, or to an `ERR_INTERNAL` error if `func` panicked.
+    pub fn spawn<F, R>(&self, func: F) -> AfTask<R>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let completion = Completion::new();
+        let done = completion.clone();
+        let job: Job = Box::new(move || {
+            let result = catch_unwind(AssertUnwindSafe(|| try_af(func)));
+            done.complete(result.map_err(panic_error).and_then(|result| result));
+        });
+        // The receiver lives as long as the executor, sending only fails if it has shut down
+        if let Err(mpsc::SendError(_)) = self.sender.as_ref().unwrap().send(job) {
+            completion.complete(Err(ArrayFireError::with_message(
+                AfError::ERR_INTERNAL,
+                String::from("executor thread has stopped"),
+            )));
+        }
+        AfTask { completion }
+    }
+}
+
+impl Drop for AfExecutor {
+    fn drop(&mut self) {
+        drop(self.sender.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Future returned by [AfExecutor::spawn](./struct.AfExecutor.html#method.spawn)
+pub struct AfTask<R> {
+    completion: Completion<R>,
+}
+
+impl<R> AfTask<R> {
+    /// Check if the closure has finished running, without blocking
+    pub fn is_ready(&self) -> bool {
+        self.completion.is_complete()
+    }
+}
+
+impl<R> Future for AfTask<R> {
+    type Output = AfResult<R>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.completion.poll_result(cx)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::AfExecutor;
+    use crate::core::{AfError, Array, Backend};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::thread::{self, Thread};
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    /// Minimal executor driving a future to completion on the current thread
+    pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = Box::pin(future);
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match Pin::as_mut(&mut future).poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn executor_runs_closures_on_its_thread() {
+        let executor = AfExecutor::new(Backend::DEFAULT, 0).unwrap();
+        let first = executor.spawn(|| {
+            let a = Array::new(&[1.0f32, 2.0, 3.0], crate::dim4!(3));
+            let mut host = vec![0.0f32; 3];
+            a.host(&mut host);
+            host
+        });
+        let second = executor.spawn(crate::core::get_device);
+        assert_eq!(block_on(first).unwrap(), vec![1.0, 2.0, 3.0]);
+        assert_eq!(block_on(second).unwrap(), 0);
+
+        let failed = executor.spawn(|| {
+            let mut host = vec![0.0f32; 2];
+            Array::new(&[1.0f32, 2.0, 3.0], crate::dim4!(3)).host(&mut host);
+        });
+        assert_eq!(block_on(failed).unwrap_err().code(), AfError::ERR_SIZE);
+        let panicked = executor.spawn(|| panic!("not an ArrayFire error"));
+        assert_eq!(
+            block_on(panicked).unwrap_err().code(),
+            AfError::ERR_INTERNAL
+        );
+        assert_eq!(block_on(executor.spawn(|| 7)).unwrap(), 7);
+
+        assert!(AfExecutor::new(Backend::DEFAULT, 1 << 20).is_err());
+    }
+}
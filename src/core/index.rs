@@ -1,6 +1,9 @@
 use super::array::Array;
+#[cfg(feature = "data")]
+use super::data::{constant, flat, moddims, tile, ConstGenerator};
 use super::defines::AfError;
-use super::error::HANDLE_ERROR;
+use super::dim4::Dim4;
+use super::error::{handle_wrapper_error, AfResult, ArrayFireError, HANDLE_ERROR};
 use super::seq::Seq;
 use super::util::{af_array, af_index_t, dim_t, HasAfEnum, IndexableType};
 
@@ -8,6 +11,7 @@ use libc::{c_double, c_int, c_uint};
 use std::default::Default;
 use std::marker::PhantomData;
 use std::mem;
+use std::ops::{Range, RangeFrom, RangeFull, RangeInclusive};
 
 extern "C" {
     fn af_create_indexers(indexers: *mut af_index_t) -> c_int;
@@ -20,6 +24,9 @@ extern "C" {
     ) -> c_int;
     fn af_release_indexers(indexers: af_index_t) -> c_int;
 
+    #[cfg(feature = "data")]
+    fn af_where(out: *mut af_array, input: af_array) -> c_int;
+
     fn af_index(
         out: *mut af_array,
         input: af_array,
@@ -110,6 +117,8 @@ extern "C" {
 pub struct Indexer<'object> {
     handle: af_index_t,
     count: usize,
+    /// Set once an index object reported an error, the Indexer selects nothing afterwards
+    failed: bool,
     marker: PhantomData<&'object ()>,
 }
 
@@ -174,6 +183,443 @@ where
     }
 }
 
+/// Sets the span of all elements along `dim`
+fn set_span(idxr: &mut Indexer, dim: u32, is_batch: Option<bool>) {
+    Seq::<f64>::default().set(idxr, dim, is_batch);
+}
+
+/// Sets the elements `begin..=end` along `dim`, negative `end` counts from the last element
+fn set_inclusive(idxr: &mut Indexer, dim: u32, is_batch: Option<bool>, begin: f64, end: f64) {
+    Seq::new(begin, end, 1.0).set(idxr, dim, is_batch);
+}
+
+/// Enables Rust ranges to be used to index another Array
+///
+/// Ranges follow Rust conventions: the end of `begin..end` is exclusive and
+/// `begin..` extends to the last element along the dimension. Since the length of the
+/// dimension isn't known here, empty ranges such as `3..3` are reported as `ERR_ARG`, after
+/// which the Indexer selects no elements. Use [Array::i](./struct.Array.html#method.i) for
+/// bounds checked indexing.
+macro_rules! range_indexable_def {
+    ($($idx_type: ty),+) => {
+        $(
+            impl Indexable for Range<$idx_type> {
+                fn set(&self, idxr: &mut Indexer, dim: u32, is_batch: Option<bool>) {
+                    if self.start >= self.end {
+                        idxr.failed = true;
+                        handle_wrapper_error(
+                            AfError::ERR_ARG,
+                            &format!("range {}..{} is empty", self.start, self.end),
+                        );
+                        return;
+                    }
+                    set_inclusive(
+                        idxr,
+                        dim,
+                        is_batch,
+                        self.start as f64,
+                        (self.end - 1) as f64,
+                    );
+                }
+            }
+
+            impl Indexable for RangeInclusive<$idx_type> {
+                fn set(&self, idxr: &mut Indexer, dim: u32, is_batch: Option<bool>) {
+                    if self.start() > self.end() {
+                        idxr.failed = true;
+                        handle_wrapper_error(
+                            AfError::ERR_ARG,
+                            &format!("range {}..={} is empty", self.start(), self.end()),
+                        );
+                        return;
+                    }
+                    set_inclusive(
+                        idxr,
+                        dim,
+                        is_batch,
+                        *self.start() as f64,
+                        *self.end() as f64,
+                    );
+                }
+            }
+
+            impl Indexable for RangeFrom<$idx_type> {
+                fn set(&self, idxr: &mut Indexer, dim: u32, is_batch: Option<bool>) {
+                    set_inclusive(idxr, dim, is_batch, self.start as f64, -1.0);
+                }
+            }
+        )+
+    };
+}
+
+range_indexable_def!(i32, i64, u32, u64, usize);
+
+/// Enables `..` to be used to select all elements along a dimension
+impl Indexable for RangeFull {
+    fn set(&self, idxr: &mut Indexer, dim: u32, is_batch: Option<bool>) {
+        set_span(idxr, dim, is_batch);
+    }
+}
+
+/// Index along a single dimension for use with [Array::i](./struct.Array.html#method.i)
+///
+/// Implemented for Rust ranges, integer scalars, which select a single element,
+/// and references to index Arrays.
+pub trait AxisIndex {
+    /// Validate the index against a dimension of length `len` and set it on `idxr`
+    ///
+    /// Returns the number of elements selected along the dimension. Nothing is set
+    /// on `idxr` when no elements are selected.
+    fn set_axis(&self, idxr: &mut Indexer, dim: u32, len: u64) -> AfResult<u64>;
+}
+
+/// Sets the elements `begin..end` along `dim` after checking them against `len`
+fn set_checked_range(
+    idxr: &mut Indexer,
+    dim: u32,
+    len: u64,
+    begin: i128,
+    end: i128,
+) -> AfResult<u64> {
+    if begin < 0 || end < begin || end > len as i128 {
+        return Err(ArrayFireError::with_message(
+            AfError::ERR_SIZE,
+            format!(
+                "range {}..{} is out of bounds for dimension {} of length {}",
+                begin, end, dim, len
+            ),
+        ));
+    }
+    if begin < end {
+        set_inclusive(idxr, dim, None, begin as f64, (end - 1) as f64);
+        idxr.count += 1;
+    }
+    Ok((end - begin) as u64)
+}
+
+macro_rules! axis_index_def {
+    ($($idx_type: ty),+) => {
+        $(
+            impl AxisIndex for $idx_type {
+                fn set_axis(&self, idxr: &mut Indexer, dim: u32, len: u64) -> AfResult<u64> {
+                    let index = *self as i128;
+                    if index < 0 || index >= len as i128 {
+                        return Err(ArrayFireError::with_message(
+                            AfError::ERR_SIZE,
+                            format!(
+                                "index {} is out of bounds for dimension {} of length {}",
+                                index, dim, len
+                            ),
+                        ));
+                    }
+                    set_checked_range(idxr, dim, len, index, index + 1)
+                }
+            }
+
+            impl AxisIndex for Range<$idx_type> {
+                fn set_axis(&self, idxr: &mut Indexer, dim: u32, len: u64) -> AfResult<u64> {
+                    set_checked_range(idxr, dim, len, self.start as i128, self.end as i128)
+                }
+            }
+
+            impl AxisIndex for RangeInclusive<$idx_type> {
+                fn set_axis(&self, idxr: &mut Indexer, dim: u32, len: u64) -> AfResult<u64> {
+                    let end = *self.end() as i128 + 1;
+                    set_checked_range(idxr, dim, len, *self.start() as i128, end)
+                }
+            }
+
+            impl AxisIndex for RangeFrom<$idx_type> {
+                fn set_axis(&self, idxr: &mut Indexer, dim: u32, len: u64) -> AfResult<u64> {
+                    set_checked_range(idxr, dim, len, self.start as i128, len as i128)
+                }
+            }
+        )+
+    };
+}
+
+axis_index_def!(i32, i64, u32, u64, usize);
+
+impl AxisIndex for RangeFull {
+    fn set_axis(&self, idxr: &mut Indexer, dim: u32, len: u64) -> AfResult<u64> {
+        if len > 0 {
+            set_span(idxr, dim, None);
+            idxr.count += 1;
+        }
+        Ok(len)
+    }
+}
+
+/// Index Arrays select the elements at the positions they hold
+///
+/// The values of index Arrays are not checked against the length of the dimension.
+impl<T> AxisIndex for &Array<T>
+where
+    T: HasAfEnum + IndexableType,
+{
+    fn set_axis(&self, idxr: &mut Indexer, dim: u32, _len: u64) -> AfResult<u64> {
+        let elements = self.elements() as u64;
+        if elements > 0 {
+            Indexable::set(*self, idxr, dim, None);
+            idxr.count += 1;
+        }
+        Ok(elements)
+    }
+}
+
+/// Indices for up to four dimensions for use with [Array::i](./struct.Array.html#method.i)
+///
+/// Implemented for single [AxisIndex](./trait.AxisIndex.html) values, which index the first
+/// dimension, and tuples of up to four of them. Dimensions without an index are kept whole.
+pub trait ArrayIndex {
+    /// Set the indices on `idxr` and return the dimensions of the indexed Array
+    fn set_indices(&self, idxr: &mut Indexer, dims: Dim4) -> AfResult<Dim4>;
+}
+
+impl<A: AxisIndex> ArrayIndex for A {
+    fn set_indices(&self, idxr: &mut Indexer, dims: Dim4) -> AfResult<Dim4> {
+        let mut out = *dims.get();
+        out[0] = self.set_axis(idxr, 0, dims[0])?;
+        Ok(Dim4::new(&out))
+    }
+}
+
+macro_rules! array_index_tuple_def {
+    ($(($name: ident, $dim: tt)),+) => {
+        impl<$($name: AxisIndex),+> ArrayIndex for ($($name,)+) {
+            fn set_indices(&self, idxr: &mut Indexer, dims: Dim4) -> AfResult<Dim4> {
+                let mut out = *dims.get();
+                $(
+                    out[$dim] = self.$dim.set_axis(idxr, $dim, dims[$dim])?;
+                )+
+                Ok(Dim4::new(&out))
+            }
+        }
+    };
+}
+
+array_index_tuple_def!((A, 0));
+array_index_tuple_def!((A, 0), (B, 1));
+array_index_tuple_def!((A, 0), (B, 1), (C, 2));
+array_index_tuple_def!((A, 0), (B, 1), (C, 2), (D, 3));
+
+impl<T: HasAfEnum> Array<T> {
+    /// Index the Array using Rust ranges, integers and index Arrays
+    ///
+    /// Each element of the tuple `indices` indexes one dimension, starting with the first one.
+    /// Ranges exclude their end as usual in Rust, integers select a single element while
+    /// keeping the dimension and `..` selects all elements. Dimensions beyond the tuple are
+    /// kept whole.
+    ///
+    /// # Return Values
+    ///
+    /// The indexed Array. Fails with `ERR_SIZE` if a range or integer is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arrayfire::{randu, Array, Dim4};
+    /// let a = randu::<f32>(Dim4::new(&[4, 6, 3, 1]));
+    /// let b = a.i((.., 2..5, 0)).unwrap();
+    /// assert_eq!(b.dims(), Dim4::new(&[4, 3, 1, 1]));
+    ///
+    /// let rows = Array::new(&[3u32, 0], Dim4::new(&[2, 1, 1, 1]));
+    /// let c = a.i((&rows, 1..=2)).unwrap();
+    /// assert_eq!(c.dims(), Dim4::new(&[2, 2, 3, 1]));
+    ///
+    /// assert!(a.i((.., 4..7)).is_err());
+    /// ```
+    pub fn i<I: ArrayIndex>(&self, indices: I) -> AfResult<Array<T>> {
+        let mut idxr = Indexer::default();
+        let out_dims = indices.set_indices(&mut idxr, self.dims())?;
+        if out_dims.elements() == 0 {
+            return Ok(Array::new_empty(out_dims));
+        }
+        Ok(index_gen(self, idxr))
+    }
+
+    /// Assign `rhs` to the part of the Array selected by `indices`
+    ///
+    /// `indices` follows the conventions of [Array::i](./struct.Array.html#method.i).
+    /// `rhs` has to have as many elements as the selected part.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arrayfire::{constant, Dim4};
+    /// let mut a = constant(0.0f32, Dim4::new(&[4, 4, 1, 1]));
+    /// a.set_i((1..3, 0), &constant(1.0f32, Dim4::new(&[2, 1, 1, 1]))).unwrap();
+    /// ```
+    pub fn set_i<I: ArrayIndex>(&mut self, indices: I, rhs: &Array<T>) -> AfResult<()> {
+        let mut idxr = Indexer::default();
+        let out_dims = indices.set_indices(&mut idxr, self.dims())?;
+        if rhs.elements() as u64 != out_dims.elements() {
+            return Err(ArrayFireError::with_message(
+                AfError::ERR_SIZE,
+                format!(
+                    "cannot assign an Array of dims {} to a selection of dims {}",
+                    rhs.dims(),
+                    out_dims
+                ),
+            ));
+        }
+        if out_dims.elements() == 0 {
+            return Ok(());
+        }
+        assign_gen(self, &idxr, rhs);
+        Ok(())
+    }
+}
+
+/// Values assigned by [Array::masked_assign](./struct.Array.html#method.masked_assign)
+///
+/// Implemented for scalars, which are assigned to every selected element, and for Arrays,
+/// which either have one element per selected element or the dimensions of the target
+/// Array, in which case the elements at the selected positions are assigned.
+#[cfg(feature = "data")]
+pub trait MaskedValue<T: HasAfEnum> {
+    /// Returns the `count` values to assign given the mask `mask` of the target Array
+    fn masked_values(&self, mask: &Array<bool>, count: u64) -> AfResult<Array<T>>;
+}
+
+#[cfg(feature = "data")]
+impl<T> MaskedValue<T> for T
+where
+    T: HasAfEnum + ConstGenerator<OutType = T>,
+{
+    fn masked_values(&self, _mask: &Array<bool>, count: u64) -> AfResult<Array<T>> {
+        Ok(constant(*self, Dim4::new(&[count, 1, 1, 1])))
+    }
+}
+
+#[cfg(feature = "data")]
+impl<T: HasAfEnum> MaskedValue<T> for &Array<T> {
+    fn masked_values(&self, mask: &Array<bool>, count: u64) -> AfResult<Array<T>> {
+        if self.dims() == mask.dims() {
+            self.masked(mask)
+        } else if self.elements() as u64 == count {
+            Ok(flat(self))
+        } else {
+            Err(ArrayFireError::with_message(
+                AfError::ERR_SIZE,
+                format!(
+                    "cannot assign an Array of dims {} to {} masked elements",
+                    self.dims(),
+                    count
+                ),
+            ))
+        }
+    }
+}
+
+#[cfg(feature = "data")]
+impl<T: HasAfEnum> Array<T> {
+    /// Broadcasts `mask` to the dimensions of the Array
+    fn broadcast_mask(&self, mask: &Array<bool>) -> AfResult<Array<bool>> {
+        let dims = self.dims();
+        let mask_dims = mask.dims();
+        if mask_dims == dims {
+            return Ok(mask.clone());
+        }
+        let mut reps = [1u64; 4];
+        for d in 0..4 {
+            if mask_dims[d] == dims[d] {
+                continue;
+            }
+            if mask_dims[d] != 1 {
+                return Err(ArrayFireError::with_message(
+                    AfError::ERR_SIZE,
+                    format!(
+                        "mask of dims {} can not be broadcast to dims {}",
+                        mask_dims, dims
+                    ),
+                ));
+            }
+            reps[d] = dims[d];
+        }
+        Ok(tile(mask, Dim4::new(&reps)))
+    }
+
+    /// Returns the linear positions of the elements selected by the broadcast `mask`
+    fn mask_positions(mask: &Array<bool>) -> Array<u32> {
+        let mut temp: af_array = std::ptr::null_mut();
+        let err_val = unsafe { af_where(&mut temp as *mut af_array, mask.get()) };
+        HANDLE_ERROR(AfError::from(err_val));
+        temp.into()
+    }
+
+    /// Select the elements for which `mask` is true
+    ///
+    /// `mask` has to have the dimensions of the Array or be broadcastable to them, i.e. have
+    /// either the same length or length one along each dimension.
+    ///
+    /// # Return Values
+    ///
+    /// A column vector with the selected elements in column major order. Fails with
+    /// `ERR_SIZE` if `mask` can't be broadcast.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arrayfire::{gt, Array, Dim4};
+    /// let a = Array::new(&[1.0f32, -2.0, 3.0, -4.0], Dim4::new(&[2, 2, 1, 1]));
+    /// let positive = a.masked(&gt(&a, &0.0f32, false)).unwrap();
+    ///
+    /// let mut host = vec![0.0f32; positive.elements()];
+    /// positive.host(&mut host);
+    /// assert_eq!(host, vec![1.0, 3.0]);
+    /// ```
+    pub fn masked(&self, mask: &Array<bool>) -> AfResult<Array<T>> {
+        let mask = self.broadcast_mask(mask)?;
+        let positions = Self::mask_positions(&mask);
+        if positions.elements() == 0 {
+            return Ok(Array::new_empty(Dim4::new(&[0, 1, 1, 1])));
+        }
+        let mut idxr = Indexer::default();
+        idxr.set_index(&positions, 0, None);
+        Ok(index_gen(&flat(self), idxr))
+    }
+
+    /// Assign `value` to the elements for which `mask` is true
+    ///
+    /// `mask` follows the broadcasting rules of [masked](./struct.Array.html#method.masked).
+    /// `value` is either a scalar, an Array with one element per selected element or
+    /// an Array with the dimensions of `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arrayfire::{isnan, Array, Dim4};
+    /// let mut a = Array::new(&[1.0f32, f32::NAN, 3.0, f32::NAN], Dim4::new(&[4, 1, 1, 1]));
+    /// let nans = isnan(&a);
+    /// a.masked_assign(&nans, 0.0f32).unwrap();
+    ///
+    /// let mut host = vec![0.0f32; 4];
+    /// a.host(&mut host);
+    /// assert_eq!(host, vec![1.0, 0.0, 3.0, 0.0]);
+    /// ```
+    pub fn masked_assign<V: MaskedValue<T>>(
+        &mut self,
+        mask: &Array<bool>,
+        value: V,
+    ) -> AfResult<()> {
+        let mask = self.broadcast_mask(mask)?;
+        let positions = Self::mask_positions(&mask);
+        let count = positions.elements() as u64;
+        let values = value.masked_values(&mask, count)?;
+        if count == 0 {
+            return Ok(());
+        }
+        let dims = self.dims();
+        let mut target = flat(self);
+        let mut idxr = Indexer::default();
+        idxr.set_index(&positions, 0, None);
+        assign_gen(&mut target, &idxr, &values);
+        *self = moddims(&target, dims);
+        Ok(())
+    }
+}
+
 impl<'object> Default for Indexer<'object> {
     fn default() -> Self {
         let mut temp: af_index_t = std::ptr::null_mut();
@@ -182,6 +628,7 @@ impl<'object> Default for Indexer<'object> {
         Self {
             handle: temp,
             count: 0,
+            failed: false,
             marker: PhantomData,
         }
     }
@@ -197,6 +644,7 @@ impl<'object> Indexer<'object> {
         Self {
             handle: temp,
             count: 0,
+            failed: false,
             marker: PhantomData,
         }
     }
@@ -578,6 +1026,17 @@ pub fn index_gen<T>(input: &Array<T>, indices: Indexer) -> Array<T>
 where
     T: HasAfEnum,
 {
+    index_gen_ref(input, &indices)
+}
+
+/// Index an Array without consuming the Indexer, so that it can be used for assignment later
+pub(crate) fn index_gen_ref<T>(input: &Array<T>, indices: &Indexer) -> Array<T>
+where
+    T: HasAfEnum,
+{
+    if indices.failed {
+        return Array::new_empty(Dim4::new(&[0, 1, 1, 1]));
+    }
     let mut temp: af_array = std::ptr::null_mut();
     let err_val = unsafe {
         af_index_gen(
@@ -627,6 +1086,9 @@ pub fn assign_gen<T>(lhs: &mut Array<T>, indices: &Indexer, rhs: &Array<T>)
 where
     T: HasAfEnum,
 {
+    if indices.failed {
+        return;
+    }
     let mut temp: af_array = std::ptr::null_mut();
     let err_val = unsafe {
         af_assign_gen(
@@ -924,4 +1386,116 @@ mod tests {
 
         assert_eq!(gold, res);
     }
+
+    #[test]
+    fn range_index() {
+        set_device(0);
+
+        let values: Vec<u32> = (0..24).collect();
+        let a = Array::new(&values, dim4!(4, 6));
+
+        let b = a.i((1..3, 2)).unwrap();
+        assert_eq!(b.dims(), dim4!(2, 1));
+        let mut res = vec![0u32; 2];
+        b.host(&mut res);
+        assert_eq!(res, vec![9, 10]);
+
+        let c = a.i((.., 4..)).unwrap();
+        assert_eq!(c.dims(), dim4!(4, 2));
+        let mut res = vec![0u32; 8];
+        c.host(&mut res);
+        assert_eq!(res, (16..24).collect::<Vec<u32>>());
+
+        let idx = Array::new(&[3u32, 0], dim4!(2));
+        let d = a.i((&idx, 0..=1)).unwrap();
+        let mut res = vec![0u32; 4];
+        d.host(&mut res);
+        assert_eq!(res, vec![3, 0, 7, 4]);
+
+        assert_eq!(a.i((.., 3..3)).unwrap().dims(), dim4!(4, 0));
+        assert!(a.i((4, 0)).is_err());
+        assert!(a.i((.., 5..7)).is_err());
+        let (start, end) = (3, 2);
+        assert!(a.i((0, start..end)).is_err());
+
+        let mut idxr = Indexer::default();
+        idxr.set_index(&(1..3), 0, None);
+        idxr.set_index(&(5usize..), 1, None);
+        let e = index_gen(&a, idxr);
+        let mut res = vec![0u32; 2];
+        e.host(&mut res);
+        assert_eq!(res, vec![21, 22]);
+
+        let empty = crate::try_af(|| {
+            let mut idxr = Indexer::default();
+            idxr.set_index(&(2..2), 0, None);
+            index_gen(&a, idxr)
+        });
+        assert_eq!(empty.unwrap_err().code(), crate::AfError::ERR_ARG);
+        let empty = crate::with_error_handler(crate::Callback::new(|_| {}), || {
+            let range = start..=end;
+            let mut idxr = Indexer::default();
+            idxr.set_index(&range, 0, None);
+            index_gen(&a, idxr)
+        });
+        assert_eq!(empty.elements(), 0);
+    }
+
+    #[test]
+    fn range_assign() {
+        set_device(0);
+
+        let mut a = Array::new(&[0i32; 6], dim4!(3, 2));
+        a.set_i((1.., 1), &Array::new(&[7i32, 8], dim4!(2)))
+            .unwrap();
+        let mut res = vec![0i32; 6];
+        a.host(&mut res);
+        assert_eq!(res, vec![0, 0, 0, 0, 7, 8]);
+
+        let err = a
+            .set_i(0, &Array::new(&[1i32, 2, 3], dim4!(3)))
+            .unwrap_err();
+        assert_eq!(err.code(), crate::AfError::ERR_SIZE);
+    }
+
+    #[test]
+    fn masked_select_and_assign() {
+        set_device(0);
+
+        let mut a = Array::new(&[1i32, -2, 3, -4, 5, -6], dim4!(2, 3));
+        let mask = Array::new(&[true, false, true, true, false, false], dim4!(2, 3));
+        let selected = a.masked(&mask).unwrap();
+        let mut res = vec![0i32; 3];
+        selected.host(&mut res);
+        assert_eq!(res, vec![1, 3, -4]);
+
+        let first_row = Array::new(&[true, false], dim4!(2));
+        let mut res = vec![0i32; 3];
+        a.masked(&first_row).unwrap().host(&mut res);
+        assert_eq!(res, vec![1, 3, 5]);
+        assert!(a.masked(&Array::new(&[true; 3], dim4!(3))).is_err());
+
+        let none = Array::new(&[false; 6], dim4!(2, 3));
+        assert_eq!(a.masked(&none).unwrap().elements(), 0);
+
+        a.masked_assign(&first_row, 0).unwrap();
+        let mut res = vec![0i32; 6];
+        a.host(&mut res);
+        assert_eq!(res, vec![0, -2, 0, -4, 0, -6]);
+
+        a.masked_assign(&mask, &Array::new(&[7i32, 8, 9], dim4!(3)))
+            .unwrap();
+        a.host(&mut res);
+        assert_eq!(res, vec![7, -2, 8, 9, 0, -6]);
+
+        let other = Array::new(&[10i32, 20, 30, 40, 50, 60], dim4!(2, 3));
+        a.masked_assign(&mask, &other).unwrap();
+        a.host(&mut res);
+        assert_eq!(res, vec![10, -2, 30, 40, 0, -6]);
+
+        let err = a
+            .masked_assign(&mask, &Array::new(&[1i32, 2], dim4!(2)))
+            .unwrap_err();
+        assert_eq!(err.code(), crate::AfError::ERR_SIZE);
+    }
 }
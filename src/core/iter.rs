@@ -0,0 +1,260 @@
+use super::array::Array;
+use super::defines::AfError;
+use super::dim4::Dim4;
+use super::error::{AfResult, ArrayFireError, HANDLE_ERROR};
+use super::util::{af_array, HasAfEnum};
+
+#[cfg(feature = "indexing")]
+use super::index::index;
+#[cfg(feature = "indexing")]
+use super::seq::Seq;
+
+use libc::{c_int, c_void};
+use std::iter::FromIterator;
+
+extern "C" {
+    fn af_get_data_ptr(data: *mut c_void, arr: af_array) -> c_int;
+}
+
+fn from_vec<T: HasAfEnum>(values: Vec<T>, dims: Dim4) -> Array<T> {
+    if values.is_empty() {
+        Array::new_empty(dims)
+    } else {
+        Array::new(&values, dims)
+    }
+}
+
+/// Collect the elements of an iterator into a column vector
+///
+/// # Examples
+///
+/// ```rust
+/// use arrayfire::{Array, Dim4};
+/// let squares: Array<i32> = (1..5).map(|v| v * v).collect();
+/// assert_eq!(squares.dims(), Dim4::new(&[4, 1, 1, 1]));
+/// ```
+impl<T: HasAfEnum> FromIterator<T> for Array<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let values: Vec<T> = iter.into_iter().collect();
+        let dims = Dim4::new(&[values.len() as u64, 1, 1, 1]);
+        from_vec(values, dims)
+    }
+}
+
+/// Collect the elements of an iterator into an Array of given dimensions
+///
+/// Implemented for all iterators over types that an Array can hold.
+pub trait CollectArray: Iterator + Sized
+where
+    Self::Item: HasAfEnum,
+{
+    /// Collect the elements, in column major order, into an Array of dimensions `dims`
+    ///
+    /// # Return Values
+    ///
+    /// The Array. Fails with `ERR_SIZE` if the iterator doesn't yield exactly
+    /// `dims.elements()` values.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arrayfire::{CollectArray, Dim4};
+    /// let a = (0..6).map(|v| v as f32).collect_with_dims(Dim4::new(&[2, 3, 1, 1])).unwrap();
+    /// assert_eq!(a.dims(), Dim4::new(&[2, 3, 1, 1]));
+    /// ```
+    fn collect_with_dims(self, dims: Dim4) -> AfResult<Array<Self::Item>> {
+        let values: Vec<Self::Item> = self.collect();
+        if values.len() as u64 != dims.elements() {
+            return Err(ArrayFireError::with_message(
+                AfError::ERR_SIZE,
+                format!(
+                    "{} values can not fill an Array of dims {}",
+                    values.len(),
+                    dims
+                ),
+            ));
+        }
+        Ok(from_vec(values, dims))
+    }
+}
+
+impl<I> CollectArray for I
+where
+    I: Iterator,
+    I::Item: HasAfEnum,
+{
+}
+
+impl<T: HasAfEnum> Array<T> {
+    /// Iterate over the elements of the Array in column major order
+    ///
+    /// The Array is copied to host memory once, when this method is called. If the copy fails
+    /// and the error handler returns, the iterator yields no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arrayfire::{Array, Dim4};
+    /// let a = Array::new(&[1u8, 2, 3, 4], Dim4::new(&[2, 2, 1, 1]));
+    /// assert_eq!(a.iter_host().sum::<u8>(), 10);
+    /// ```
+    pub fn iter_host(&self) -> std::vec::IntoIter<T> {
+        let len = self.elements();
+        let mut values: Vec<T> = Vec::with_capacity(len);
+        if len > 0 {
+            let err_val =
+                unsafe { af_get_data_ptr(values.as_mut_ptr() as *mut c_void, self.get()) };
+            let err = AfError::from(err_val);
+            HANDLE_ERROR(err);
+            if err == AfError::SUCCESS {
+                // af_get_data_ptr initialized all `len` elements
+                unsafe { values.set_len(len) };
+            }
+        }
+        values.into_iter()
+    }
+
+    /// Iterate over chunks of `size` indices along dimension `dim`
+    ///
+    /// Every chunk but the last has `size` indices along `dim` and spans the other dimensions
+    /// fully. The last chunk holds the remaining indices.
+    ///
+    /// # Return Values
+    ///
+    /// The iterator. Fails with `ERR_ARG` if `size` is zero or `dim` is larger than 3.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use arrayfire::{randu, Dim4};
+    /// let samples = randu::<f32>(Dim4::new(&[10, 100, 1, 1]));
+    /// for batch in samples.chunks(1, 32).unwrap() {
+    ///     assert!(batch.dims()[1] <= 32);
+    /// }
+    /// ```
+    #[cfg(feature = "indexing")]
+    pub fn chunks(&self, dim: usize, size: u64) -> AfResult<Chunks<'_, T>> {
+        if size == 0 || dim > 3 {
+            return Err(ArrayFireError::with_message(
+                AfError::ERR_ARG,
+                format!("can not split dimension {} into chunks of {}", dim, size),
+            ));
+        }
+        Ok(Chunks {
+            array: self,
+            dim,
+            size,
+            start: 0,
+            end: self.dims()[dim],
+        })
+    }
+}
+
+/// Iterator over chunks of an Array along a dimension
+///
+/// Created by [Array::chunks](./struct.Array.html#method.chunks).
+#[cfg(feature = "indexing")]
+pub struct Chunks<'a, T: HasAfEnum> {
+    array: &'a Array<T>,
+    dim: usize,
+    size: u64,
+    start: u64,
+    end: u64,
+}
+
+#[cfg(feature = "indexing")]
+impl<'a, T: HasAfEnum> Iterator for Chunks<'a, T> {
+    type Item = Array<T>;
+
+    fn next(&mut self) -> Option<Array<T>> {
+        if self.start >= self.end {
+            return None;
+        }
+        let first = self.start;
+        let last = (first + self.size).min(self.end) - 1;
+        self.start = last + 1;
+
+        let dims = self.array.dims();
+        if dims.elements() == 0 {
+            let mut chunk_dims = dims;
+            chunk_dims[self.dim] = last + 1 - first;
+            return Some(Array::new_empty(chunk_dims));
+        }
+        let mut seqs = [Seq::<f64>::default(); 4];
+        seqs[self.dim] = Seq::new(first as f64, last as f64, 1.0);
+        let last_dim = (0..4).rev().find(|&i| dims[i] > 1).unwrap_or(0);
+        Some(index(self.array, &seqs[..=self.dim.max(last_dim)]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.end - self.start).div_ceil(self.size) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+#[cfg(feature = "indexing")]
+impl<'a, T: HasAfEnum> ExactSizeIterator for Chunks<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::super::array::Array;
+    use super::super::defines::AfError;
+    use super::super::device::set_device;
+    use super::CollectArray;
+    use crate::dim4;
+
+    #[test]
+    fn collect_and_iterate() {
+        set_device(0);
+        let a: Array<i32> = (1..=6).collect();
+        assert_eq!(a.dims(), dim4!(6));
+        assert_eq!(a.iter_host().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6]);
+
+        let b = a
+            .iter_host()
+            .map(|v| v * 10)
+            .collect_with_dims(dim4!(2, 3))
+            .unwrap();
+        assert_eq!(b.dims(), dim4!(2, 3));
+        assert_eq!(b.iter_host().last(), Some(60));
+        let err = (0..5).collect_with_dims(dim4!(2, 3)).unwrap_err();
+        assert_eq!(err.code(), AfError::ERR_SIZE);
+
+        let empty: Array<f32> = std::iter::empty().collect();
+        assert_eq!(empty.elements(), 0);
+        assert_eq!(empty.iter_host().count(), 0);
+    }
+
+    #[cfg(feature = "indexing")]
+    #[test]
+    fn chunks_along_dimension() {
+        set_device(0);
+        let a = (0..24).collect_with_dims(dim4!(2, 3, 4)).unwrap();
+
+        let chunks = a.chunks(2, 3).unwrap();
+        assert_eq!(chunks.len(), 2);
+        let chunks: Vec<Array<i32>> = chunks.collect();
+        assert_eq!(chunks[0].dims(), dim4!(2, 3, 3));
+        assert_eq!(chunks[1].dims(), dim4!(2, 3, 1));
+        assert_eq!(
+            chunks[1].iter_host().collect::<Vec<_>>(),
+            (18..24).collect::<Vec<_>>()
+        );
+
+        let rows: Vec<Vec<i32>> = a
+            .chunks(0, 1)
+            .unwrap()
+            .map(|row| row.iter_host().collect())
+            .collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1][..3], [1, 3, 5]);
+
+        assert_eq!(a.chunks(3, 5).unwrap().count(), 1);
+        assert_eq!(a.chunks(1, 0).err().unwrap().code(), AfError::ERR_ARG);
+
+        let empty = Array::<f32>::new_empty(dim4!(0, 5));
+        let dims: Vec<_> = empty.chunks(1, 2).unwrap().map(|c| c.dims()).collect();
+        assert_eq!(dims, vec![dim4!(0, 2), dim4!(0, 2), dim4!(0, 1)]);
+        assert_eq!(empty.chunks(0, 2).unwrap().count(), 0);
+    }
+}
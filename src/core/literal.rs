@@ -0,0 +1,79 @@
+use super::array::Array;
+use super::dim4::Dim4;
+use super::util::HasAfEnum;
+
+/// Nested Rust arrays in row major notation
+///
+/// This is the input of the [array!](./macro.array.html) macro. The innermost level holds the
+/// columns of a row, the next level the rows of a matrix, and the third and fourth levels stack
+/// matrices along the third and fourth dimensions. As the levels are fixed size Rust arrays,
+/// ragged rows are rejected by the compiler.
+pub trait ArrayLiteral {
+    /// Data type of the elements
+    type Elem: HasAfEnum;
+
+    /// Create an Array in column major order with dimensions following the nesting
+    fn to_array(&self) -> Array<Self::Elem>;
+}
+
+fn from_vec<T: HasAfEnum>(values: Vec<T>, dims: Dim4) -> Array<T> {
+    if values.is_empty() {
+        Array::new_empty(dims)
+    } else {
+        Array::new(&values, dims)
+    }
+}
+
+impl<T: HasAfEnum + Copy, const R: usize> ArrayLiteral for [T; R] {
+    type Elem = T;
+
+    fn to_array(&self) -> Array<T> {
+        from_vec(self.to_vec(), Dim4::new(&[R as u64, 1, 1, 1]))
+    }
+}
+
+impl<T: HasAfEnum + Copy, const R: usize, const C: usize> ArrayLiteral for [[T; C]; R] {
+    type Elem = T;
+
+    fn to_array(&self) -> Array<T> {
+        let mut values = Vec::with_capacity(R * C);
+        for c in 0..C {
+            values.extend(self.iter().map(|row| row[c]));
+        }
+        from_vec(values, Dim4::new(&[R as u64, C as u64, 1, 1]))
+    }
+}
+
+impl<T: HasAfEnum + Copy, const S: usize, const R: usize, const C: usize> ArrayLiteral
+    for [[[T; C]; R]; S]
+{
+    type Elem = T;
+
+    fn to_array(&self) -> Array<T> {
+        let mut values = Vec::with_capacity(S * R * C);
+        for matrix in self.iter() {
+            for c in 0..C {
+                values.extend(matrix.iter().map(|row| row[c]));
+            }
+        }
+        from_vec(values, Dim4::new(&[R as u64, C as u64, S as u64, 1]))
+    }
+}
+
+impl<T, const B: usize, const S: usize, const R: usize, const C: usize> ArrayLiteral
+    for [[[[T; C]; R]; S]; B]
+where
+    T: HasAfEnum + Copy,
+{
+    type Elem = T;
+
+    fn to_array(&self) -> Array<T> {
+        let mut values = Vec::with_capacity(B * S * R * C);
+        for matrix in self.iter().flat_map(|batch| batch.iter()) {
+            for c in 0..C {
+                values.extend(matrix.iter().map(|row| row[c]));
+            }
+        }
+        from_vec(values, Dim4::new(&[R as u64, C as u64, S as u64, B as u64]))
+    }
+}
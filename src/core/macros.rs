@@ -136,6 +136,82 @@ macro_rules! dim4 {
     };
 }
 
+/// Create an Array from nested literals in row major notation
+///
+/// Each argument is a row of the Array. Up to four levels of nesting are accepted:
+///
+/// - `array![1, 2, 3]` is a column vector of dimensions `[3, 1, 1, 1]`
+/// - `array![[1, 2, 3], [4, 5, 6]]` is a matrix with 2 rows and 3 columns
+/// - a third level stacks such matrices along the third dimension, and a fourth level stacks
+///   those along the fourth dimension
+///
+/// The values are laid out in the column major order ArrayFire expects. Ragged rows fail to
+/// compile. See [ArrayLiteral](./trait.ArrayLiteral.html) for the conversion.
+///
+/// # Examples
+///
+/// ```rust
+/// use arrayfire::{array, Dim4};
+/// let a = array![[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]];
+/// assert_eq!(a.dims(), Dim4::new(&[2, 3, 1, 1]));
+///
+/// let mut host = vec![0.0f32; 6];
+/// a.host(&mut host);
+/// assert_eq!(host, [1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+///
+/// let batch = array![[[1, 2], [3, 4]], [[5, 6], [7, 8]]];
+/// assert_eq!(batch.dims(), Dim4::new(&[2, 2, 2, 1]));
+/// ```
+///
+/// ```compile_fail
+/// use arrayfire::array;
+/// let ragged = array![[1.0f32, 2.0], [3.0]];
+/// ```
+#[macro_export]
+macro_rules! array {
+    ($($row:expr),+ $(,)?) => {
+        $crate::ArrayLiteral::to_array(&[$($row),+])
+    };
+}
+
+/// Assert that two Arrays are approximately equal
+///
+/// The Arrays are compared using [close_mismatch](./fn.close_mismatch.html), with the relative
+/// tolerance `rtol` and absolute tolerance `atol`, which default to `1e-5` and `1e-8`. The
+/// optional fifth argument decides if NaN values compare equal, it defaults to false. On
+/// failure, the panic message shows the position and values of the worst offending element.
+/// Using the macro requires the `algorithm`, `arithmetic` and `data` features.
+///
+/// # Examples
+///
+/// ```rust
+/// use arrayfire::{assert_array_close, Array, Dim4};
+/// let a = Array::new(&[1.0f32, 2.0, 3.0], Dim4::new(&[3, 1, 1, 1]));
+/// let b = Array::new(&[1.0f32, 2.0001, 3.0], Dim4::new(&[3, 1, 1, 1]));
+/// assert_array_close!(a, b, 1e-3, 0.0);
+/// ```
+#[macro_export]
+macro_rules! assert_array_close {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_array_close!($left, $right, 1e-5, 1e-8, false)
+    };
+    ($left:expr, $right:expr, $rtol:expr, $atol:expr $(,)?) => {
+        $crate::assert_array_close!($left, $right, $rtol, $atol, false)
+    };
+    ($left:expr, $right:expr, $rtol:expr, $atol:expr, $equal_nan:expr $(,)?) => {
+        if let Some(mismatch) = $crate::close_mismatch(&$left, &$right, $rtol, $atol, $equal_nan) {
+            panic!(
+                "assertion failed: `{}` is not close to `{}` (rtol = {}, atol = {})\n{}",
+                stringify!($left),
+                stringify!($right),
+                $rtol,
+                $atol,
+                mismatch
+            );
+        }
+    };
+}
+
 /// Create a sequence object
 ///
 /// If type is not provided, then the Seq will default to i32 type
@@ -369,6 +445,58 @@ mod tests {
         let _dimn = dim4!(dim1d[0], dim2d[1], dim3d[2], dim4d[3]);
     }
 
+    #[test]
+    fn array_literal() {
+        set_device(0);
+        let to_vec = |a: &Array<i32>| {
+            let mut host = vec![0i32; a.elements()];
+            a.host(&mut host);
+            host
+        };
+
+        let v = array![1, 2, 3];
+        assert_eq!(v.dims(), dim4!(3));
+        assert_eq!(to_vec(&v), [1, 2, 3]);
+
+        let m = array![[1, 2, 3], [4, 5, 6],];
+        assert_eq!(m.dims(), dim4!(2, 3));
+        assert_eq!(to_vec(&m), [1, 4, 2, 5, 3, 6]);
+
+        let s = array![[[1, 2], [3, 4], [5, 6]], [[7, 8], [9, 10], [11, 12]]];
+        assert_eq!(s.dims(), dim4!(3, 2, 2));
+        assert_eq!(to_vec(&s), [1, 3, 5, 2, 4, 6, 7, 9, 11, 8, 10, 12]);
+
+        let b = array![
+            [[[1, 2]], [[3, 4]]],
+            [[[5, 6]], [[7, 8]]],
+            [[[9, 10]], [[11, 12]]]
+        ];
+        assert_eq!(b.dims(), dim4!(1, 2, 2, 3));
+        assert_eq!(to_vec(&b), (1..=12).collect::<Vec<_>>());
+    }
+
+    #[cfg(all(
+        feature = "algorithm",
+        feature = "arithmetic",
+        feature = "data",
+        feature = "indexing"
+    ))]
+    #[test]
+    fn assert_array_close_macro() {
+        set_device(0);
+        let a = array![[1.0f32, 2.0], [3.0, 4.0]];
+        let b = array![[1.0f32, 2.5], [3.0, 4.0]];
+        assert_array_close!(a, a.copy());
+        assert_array_close!(&b, &b, 0.0, 0.0);
+        assert_array_close!(a, b, 0.0, 0.5);
+        let nan = array![f32::NAN];
+        assert_array_close!(nan, nan, 0.0, 0.0, true);
+
+        let failed = std::panic::catch_unwind(|| assert_array_close!(a, b, 0.0, 0.1));
+        let message = failed.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("worst at [0, 1, 0, 0]"));
+    }
+
     #[test]
     fn seq_construction() {
         let default_seq = seq!();
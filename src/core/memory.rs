@@ -0,0 +1,588 @@
+use super::defines::AfError;
+use super::error::{AfResult, ArrayFireError};
+use super::util::{af_memory_manager, dim_t, void_ptr};
+
+use libc::{c_char, c_float, c_int, c_uint, size_t};
+use std::collections::HashMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Mutex;
+
+type InitializeFn = unsafe extern "C" fn(af_memory_manager) -> c_int;
+type ShutdownFn = unsafe extern "C" fn(af_memory_manager) -> c_int;
+type AllocFn = unsafe extern "C" fn(
+    af_memory_manager,
+    *mut void_ptr,
+    c_int,
+    c_uint,
+    *mut dim_t,
+    c_uint,
+) -> c_int;
+type AllocatedFn = unsafe extern "C" fn(af_memory_manager, *mut size_t, void_ptr) -> c_int;
+type UnlockFn = unsafe extern "C" fn(af_memory_manager, void_ptr, c_int) -> c_int;
+type SignalMemoryCleanupFn = unsafe extern "C" fn(af_memory_manager) -> c_int;
+type PrintInfoFn = unsafe extern "C" fn(af_memory_manager, *mut c_char, c_int) -> c_int;
+type UserLockFn = unsafe extern "C" fn(af_memory_manager, void_ptr) -> c_int;
+type UserUnlockFn = unsafe extern "C" fn(af_memory_manager, void_ptr) -> c_int;
+type IsUserLockedFn = unsafe extern "C" fn(af_memory_manager, *mut c_int, void_ptr) -> c_int;
+type GetMemoryPressureFn = unsafe extern "C" fn(af_memory_manager, *mut c_float) -> c_int;
+type JitTreeExceedsMemoryPressureFn =
+    unsafe extern "C" fn(af_memory_manager, *mut c_int, size_t) -> c_int;
+type AddMemoryManagementFn = unsafe extern "C" fn(af_memory_manager, c_int);
+type RemoveMemoryManagementFn = unsafe extern "C" fn(af_memory_manager, c_int);
+
+extern "C" {
+    fn af_create_memory_manager(out: *mut af_memory_manager) -> c_int;
+    fn af_release_memory_manager(handle: af_memory_manager) -> c_int;
+    fn af_set_memory_manager(handle: af_memory_manager) -> c_int;
+    fn af_unset_memory_manager() -> c_int;
+    fn af_memory_manager_get_payload(handle: af_memory_manager, payload: *mut void_ptr) -> c_int;
+    fn af_memory_manager_set_payload(handle: af_memory_manager, payload: void_ptr) -> c_int;
+
+    fn af_memory_manager_set_initialize_fn(handle: af_memory_manager, f: InitializeFn) -> c_int;
+    fn af_memory_manager_set_shutdown_fn(handle: af_memory_manager, f: ShutdownFn) -> c_int;
+    fn af_memory_manager_set_alloc_fn(handle: af_memory_manager, f: AllocFn) -> c_int;
+    fn af_memory_manager_set_allocated_fn(handle: af_memory_manager, f: AllocatedFn) -> c_int;
+    fn af_memory_manager_set_unlock_fn(handle: af_memory_manager, f: UnlockFn) -> c_int;
+    fn af_memory_manager_set_signal_memory_cleanup_fn(
+        handle: af_memory_manager,
+        f: SignalMemoryCleanupFn,
+    ) -> c_int;
+    fn af_memory_manager_set_print_info_fn(handle: af_memory_manager, f: PrintInfoFn) -> c_int;
+    fn af_memory_manager_set_user_lock_fn(handle: af_memory_manager, f: UserLockFn) -> c_int;
+    fn af_memory_manager_set_user_unlock_fn(handle: af_memory_manager, f: UserUnlockFn) -> c_int;
+    fn af_memory_manager_set_is_user_locked_fn(
+        handle: af_memory_manager,
+        f: IsUserLockedFn,
+    ) -> c_int;
+    fn af_memory_manager_set_get_memory_pressure_fn(
+        handle: af_memory_manager,
+        f: GetMemoryPressureFn,
+    ) -> c_int;
+    fn af_memory_manager_set_jit_tree_exceeds_memory_pressure_fn(
+        handle: af_memory_manager,
+        f: JitTreeExceedsMemoryPressureFn,
+    ) -> c_int;
+    fn af_memory_manager_set_add_memory_management_fn(
+        handle: af_memory_manager,
+        f: AddMemoryManagementFn,
+    ) -> c_int;
+    fn af_memory_manager_set_remove_memory_management_fn(
+        handle: af_memory_manager,
+        f: RemoveMemoryManagementFn,
+    ) -> c_int;
+
+    fn af_memory_manager_get_active_device_id(handle: af_memory_manager, id: *mut c_int) -> c_int;
+    fn af_memory_manager_native_alloc(
+        handle: af_memory_manager,
+        ptr: *mut void_ptr,
+        size: size_t,
+    ) -> c_int;
+    fn af_memory_manager_native_free(handle: af_memory_manager, ptr: void_ptr) -> c_int;
+    fn af_memory_manager_get_max_memory_size(
+        handle: af_memory_manager,
+        size: *mut size_t,
+        id: c_int,
+    ) -> c_int;
+}
+
+fn check(err_val: c_int) -> AfResult<()> {
+    match AfError::from(err_val) {
+        AfError::SUCCESS => Ok(()),
+        err => Err(ArrayFireError::new(err)),
+    }
+}
+
+/// Access to the allocation routines of the active backend
+///
+/// Passed to [MemoryManager](./trait.MemoryManager.html) methods to obtain memory from, and return
+/// memory to, the device. Pointers are device pointers, i.e. host memory on the CPU backend,
+/// CUDA device memory on the CUDA backend and `cl_mem` objects on the OpenCL backend.
+pub struct NativeMemory {
+    handle: af_memory_manager,
+}
+
+impl NativeMemory {
+    /// Returns the id of the device on which ArrayFire is currently operating
+    pub fn active_device(&self) -> i32 {
+        let mut id: c_int = 0;
+        let err_val =
+            unsafe { af_memory_manager_get_active_device_id(self.handle, &mut id as *mut c_int) };
+        match AfError::from(err_val) {
+            AfError::SUCCESS => id,
+            _ => 0,
+        }
+    }
+
+    /// Allocate `bytes` bytes on the active device
+    pub fn alloc(&self, bytes: usize) -> AfResult<void_ptr> {
+        let mut ptr: void_ptr = std::ptr::null_mut();
+        check(unsafe {
+            af_memory_manager_native_alloc(self.handle, &mut ptr as *mut void_ptr, bytes)
+        })?;
+        Ok(ptr)
+    }
+
+    /// Free memory obtained from [alloc](./struct.NativeMemory.html#method.alloc)
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by `alloc` and must not be in use by any Array.
+    pub unsafe fn free(&self, ptr: void_ptr) -> AfResult<()> {
+        check(af_memory_manager_native_free(self.handle, ptr))
+    }
+
+    /// Returns the total memory, in bytes, of device `device`
+    pub fn max_memory_size(&self, device: i32) -> AfResult<usize> {
+        let mut size: size_t = 0;
+        check(unsafe {
+            af_memory_manager_get_max_memory_size(self.handle, &mut size as *mut size_t, device)
+        })?;
+        Ok(size)
+    }
+}
+
+/// Custom memory allocation strategy for ArrayFire
+///
+/// ArrayFire calls into the memory manager whenever it needs a buffer for an Array and
+/// whenever a buffer is no longer used. Buffers handed out by the memory manager may be kept
+/// locked by ArrayFire, by the user through [Array::lock](./struct.Array.html#method.lock) and
+/// [Array::device_ptr](./struct.Array.html#method.device_ptr), or both. This bookkeeping is done
+/// by the crate, [free](./trait.MemoryManager.html#tymethod.free) is only called once a buffer is
+/// no longer locked by anyone.
+///
+/// A memory manager applies to all devices of the backend it is installed on. Each call receives
+/// the id of the device the buffer belongs to, hence managers can keep separate pools or limits
+/// per device.
+///
+/// Methods are called while ArrayFire is executing other functions, hence implementations must
+/// not call ArrayFire functions other than those of [NativeMemory](./struct.NativeMemory.html).
+///
+/// # Examples
+///
+/// A memory manager that limits the memory in use on each device
+///
+/// ```rust,no_run
+/// use arrayfire::{set_memory_manager, AfError, MemoryManager, NativeMemory};
+/// use std::collections::HashMap;
+///
+/// struct Capped {
+///     limit: usize,
+///     in_use: HashMap<i32, usize>,
+/// }
+///
+/// impl MemoryManager for Capped {
+///     fn alloc(
+///         &mut self,
+///         native: &NativeMemory,
+///         device: i32,
+///         bytes: usize,
+///     ) -> Result<*mut libc::c_void, AfError> {
+///         let in_use = self.in_use.entry(device).or_insert(0);
+///         if *in_use + bytes > self.limit {
+///             return Err(AfError::ERR_NO_MEM);
+///         }
+///         let ptr = native.alloc(bytes).map_err(AfError::from)?;
+///         *in_use += bytes;
+///         Ok(ptr)
+///     }
+///
+///     unsafe fn free(
+///         &mut self,
+///         native: &NativeMemory,
+///         device: i32,
+///         ptr: *mut libc::c_void,
+///         bytes: usize,
+///     ) {
+///         *self.in_use.entry(device).or_insert(0) -= bytes;
+///         let _ = native.free(ptr);
+///     }
+/// }
+///
+/// set_memory_manager(Capped { limit: 1 << 30, in_use: HashMap::new() }).unwrap();
+/// ```
+pub trait MemoryManager: Send {
+    /// Called when the memory manager is installed
+    fn initialize(&mut self, _native: &NativeMemory) {}
+
+    /// Called when the memory manager is being replaced or ArrayFire shuts down
+    ///
+    /// Buffers that are still cached by the memory manager should be freed here. Buffers still
+    /// in use by Arrays are never passed to [free](#tymethod.free) during shutdown.
+    fn shutdown(&mut self, _native: &NativeMemory) {}
+
+    /// Called when device `device` starts being managed
+    fn add_device(&mut self, _device: i32) {}
+
+    /// Called when device `device` is no longer managed
+    fn remove_device(&mut self, _device: i32) {}
+
+    /// Returns a buffer of at least `bytes` bytes on device `device`
+    ///
+    /// Return `AfError::ERR_NO_MEM` to signal that the memory can not be provided.
+    fn alloc(
+        &mut self,
+        native: &NativeMemory,
+        device: i32,
+        bytes: usize,
+    ) -> Result<void_ptr, AfError>;
+
+    /// Releases buffer `ptr` of `bytes` bytes allocated on device `device`
+    ///
+    /// Implementations can free the buffer using `native` or keep it for later allocations.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` is not used by ArrayFire after this call, but it may still be accessed by
+    /// pending work on the device queue unless it was synchronized.
+    unsafe fn free(&mut self, native: &NativeMemory, device: i32, ptr: void_ptr, bytes: usize);
+
+    /// Called when ArrayFire runs low on memory and cached buffers should be released
+    fn signal_memory_cleanup(&mut self, _native: &NativeMemory) {}
+
+    /// Returns the memory pressure, a value between 0 and 1, of the active device
+    fn memory_pressure(&mut self) -> f32 {
+        0.0
+    }
+
+    /// Returns whether a JIT tree requiring `bytes` bytes should be evaluated right away
+    fn jit_tree_exceeds_memory_pressure(&mut self, _bytes: usize) -> bool {
+        false
+    }
+}
+
+/// State of a buffer handed out by the memory manager
+struct Buffer {
+    device: i32,
+    bytes: usize,
+    manager_locked: bool,
+    user_locked: bool,
+}
+
+/// Payload attached to the ArrayFire memory manager handle
+struct Payload {
+    manager: Box<dyn MemoryManager>,
+    buffers: HashMap<usize, Buffer>,
+}
+
+/// Memory manager installed on a backend, released once it is replaced
+struct Installed {
+    handle: af_memory_manager,
+    payload: *mut Mutex<Payload>,
+}
+
+unsafe impl Send for Installed {}
+
+lazy_static! {
+    static ref INSTALLED: Mutex<HashMap<i32, Installed>> = Mutex::new(HashMap::new());
+}
+
+/// Runs `f` on the payload of `handle`, converting panics to an error code
+fn with_payload<F>(handle: af_memory_manager, f: F) -> c_int
+where
+    F: FnOnce(&mut Payload, &NativeMemory) -> AfError,
+{
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let mut payload: void_ptr = std::ptr::null_mut();
+        let err_val = unsafe { af_memory_manager_get_payload(handle, &mut payload) };
+        if AfError::from(err_val) != AfError::SUCCESS || payload.is_null() {
+            return AfError::ERR_INTERNAL;
+        }
+        let payload = unsafe { &*(payload as *const Mutex<Payload>) };
+        let mut payload = match payload.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        f(&mut payload, &NativeMemory { handle })
+    }));
+    result.unwrap_or(AfError::ERR_INTERNAL) as c_int
+}
+
+/// Frees `ptr` if it is no longer locked by either ArrayFire or the user
+fn release_if_unlocked(payload: &mut Payload, native: &NativeMemory, ptr: void_ptr) {
+    let key = ptr as usize;
+    let unlocked = match payload.buffers.get(&key) {
+        Some(buffer) => !buffer.manager_locked && !buffer.user_locked,
+        None => false,
+    };
+    if unlocked {
+        if let Some(buffer) = payload.buffers.remove(&key) {
+            unsafe {
+                payload
+                    .manager
+                    .free(native, buffer.device, ptr, buffer.bytes)
+            };
+        }
+    }
+}
+
+unsafe extern "C" fn initialize_cb(handle: af_memory_manager) -> c_int {
+    with_payload(handle, |payload, native| {
+        payload.manager.initialize(native);
+        AfError::SUCCESS
+    })
+}
+
+unsafe extern "C" fn shutdown_cb(handle: af_memory_manager) -> c_int {
+    with_payload(handle, |payload, native| {
+        // Locked buffers may still back live Arrays, freeing them would leave those Arrays
+        // dangling. They are leaked instead, along with the bookkeeping of the manager.
+        let unlocked: Vec<usize> = payload
+            .buffers
+            .iter()
+            .filter(|(_, buffer)| !buffer.manager_locked && !buffer.user_locked)
+            .map(|(&ptr, _)| ptr)
+            .collect();
+        for ptr in unlocked {
+            release_if_unlocked(payload, native, ptr as void_ptr);
+        }
+        payload.manager.shutdown(native);
+        AfError::SUCCESS
+    })
+}
+
+unsafe extern "C" fn alloc_cb(
+    handle: af_memory_manager,
+    ptr: *mut void_ptr,
+    user_lock: c_int,
+    ndims: c_uint,
+    dims: *mut dim_t,
+    element_size: c_uint,
+) -> c_int {
+    let dims = std::slice::from_raw_parts(dims, ndims as usize);
+    let bytes = dims.iter().product::<dim_t>() as usize * element_size as usize;
+    with_payload(handle, |payload, native| {
+        let device = native.active_device();
+        match payload.manager.alloc(native, device, bytes) {
+            Ok(buffer) => {
+                payload.buffers.insert(
+                    buffer as usize,
+                    Buffer {
+                        device,
+                        bytes,
+                        manager_locked: user_lock == 0,
+                        user_locked: user_lock != 0,
+                    },
+                );
+                *ptr = buffer;
+                AfError::SUCCESS
+            }
+            Err(err) => err,
+        }
+    })
+}
+
+unsafe extern "C" fn allocated_cb(
+    handle: af_memory_manager,
+    size: *mut size_t,
+    ptr: void_ptr,
+) -> c_int {
+    with_payload(handle, |payload, _| {
+        *size = payload
+            .buffers
+            .get(&(ptr as usize))
+            .map_or(0, |buffer| buffer.bytes);
+        AfError::SUCCESS
+    })
+}
+
+unsafe extern "C" fn unlock_cb(
+    handle: af_memory_manager,
+    ptr: void_ptr,
+    user_unlock: c_int,
+) -> c_int {
+    with_payload(handle, |payload, native| {
+        if let Some(buffer) = payload.buffers.get_mut(&(ptr as usize)) {
+            if user_unlock != 0 {
+                buffer.user_locked = false;
+            } else {
+                buffer.manager_locked = false;
+            }
+        }
+        release_if_unlocked(payload, native, ptr);
+        AfError::SUCCESS
+    })
+}
+
+unsafe extern "C" fn signal_memory_cleanup_cb(handle: af_memory_manager) -> c_int {
+    with_payload(handle, |payload, native| {
+        payload.manager.signal_memory_cleanup(native);
+        AfError::SUCCESS
+    })
+}
+
+unsafe extern "C" fn print_info_cb(_: af_memory_manager, _: *mut c_char, _: c_int) -> c_int {
+    AfError::SUCCESS as c_int
+}
+
+unsafe extern "C" fn user_lock_cb(handle: af_memory_manager, ptr: void_ptr) -> c_int {
+    with_payload(handle, |payload, _| {
+        if let Some(buffer) = payload.buffers.get_mut(&(ptr as usize)) {
+            buffer.user_locked = true;
+        }
+        AfError::SUCCESS
+    })
+}
+
+unsafe extern "C" fn user_unlock_cb(handle: af_memory_manager, ptr: void_ptr) -> c_int {
+    with_payload(handle, |payload, native| {
+        if let Some(buffer) = payload.buffers.get_mut(&(ptr as usize)) {
+            buffer.user_locked = false;
+        }
+        release_if_unlocked(payload, native, ptr);
+        AfError::SUCCESS
+    })
+}
+
+unsafe extern "C" fn is_user_locked_cb(
+    handle: af_memory_manager,
+    out: *mut c_int,
+    ptr: void_ptr,
+) -> c_int {
+    with_payload(handle, |payload, _| {
+        *out = payload
+            .buffers
+            .get(&(ptr as usize))
+            .map_or(0, |buffer| buffer.user_locked as c_int);
+        AfError::SUCCESS
+    })
+}
+
+unsafe extern "C" fn get_memory_pressure_cb(
+    handle: af_memory_manager,
+    pressure: *mut c_float,
+) -> c_int {
+    with_payload(handle, |payload, _| {
+        *pressure = payload.manager.memory_pressure();
+        AfError::SUCCESS
+    })
+}
+
+unsafe extern "C" fn jit_tree_exceeds_memory_pressure_cb(
+    handle: af_memory_manager,
+    out: *mut c_int,
+    bytes: size_t,
+) -> c_int {
+    with_payload(handle, |payload, _| {
+        *out = payload.manager.jit_tree_exceeds_memory_pressure(bytes) as c_int;
+        AfError::SUCCESS
+    })
+}
+
+unsafe extern "C" fn add_memory_management_cb(handle: af_memory_manager, device: c_int) {
+    with_payload(handle, |payload, _| {
+        payload.manager.add_device(device);
+        AfError::SUCCESS
+    });
+}
+
+unsafe extern "C" fn remove_memory_management_cb(handle: af_memory_manager, device: c_int) {
+    with_payload(handle, |payload, _| {
+        payload.manager.remove_device(device);
+        AfError::SUCCESS
+    });
+}
+
+/// Releases the memory manager that was installed on `backend`, if any
+fn release_installed(backend: i32) -> AfResult<()> {
+    let mut installed = INSTALLED.lock().unwrap_or_else(|err| err.into_inner());
+    if let Some(previous) = installed.remove(&backend) {
+        check(unsafe { af_release_memory_manager(previous.handle) })?;
+        drop(unsafe { Box::from_raw(previous.payload) });
+    }
+    Ok(())
+}
+
+fn active_backend_id() -> i32 {
+    super::backend::get_active_backend() as i32
+}
+
+/// Install `manager` as the memory manager of the active backend
+///
+/// The memory manager replaces the default memory manager, or one installed previously, for
+/// all devices of the active backend. Buffers of Arrays created before this call must not
+/// be in use anymore, hence it is best to install memory managers before any Arrays are created.
+///
+/// When replacing a memory manager installed previously, buffers of the previous manager that
+/// are still locked by live Arrays are not freed and hence leak.
+///
+/// See [MemoryManager](./trait.MemoryManager.html) for an example.
+pub fn set_memory_manager<M: MemoryManager + 'static>(manager: M) -> AfResult<()> {
+    let mut handle: af_memory_manager = std::ptr::null_mut();
+    check(unsafe { af_create_memory_manager(&mut handle as *mut af_memory_manager) })?;
+
+    let payload = Box::into_raw(Box::new(Mutex::new(Payload {
+        manager: Box::new(manager),
+        buffers: HashMap::new(),
+    })));
+    let setup = unsafe {
+        check(af_memory_manager_set_payload(handle, payload as void_ptr))
+            .and_then(|_| check(af_memory_manager_set_initialize_fn(handle, initialize_cb)))
+            .and_then(|_| check(af_memory_manager_set_shutdown_fn(handle, shutdown_cb)))
+            .and_then(|_| check(af_memory_manager_set_alloc_fn(handle, alloc_cb)))
+            .and_then(|_| check(af_memory_manager_set_allocated_fn(handle, allocated_cb)))
+            .and_then(|_| check(af_memory_manager_set_unlock_fn(handle, unlock_cb)))
+            .and_then(|_| {
+                check(af_memory_manager_set_signal_memory_cleanup_fn(
+                    handle,
+                    signal_memory_cleanup_cb,
+                ))
+            })
+            .and_then(|_| check(af_memory_manager_set_print_info_fn(handle, print_info_cb)))
+            .and_then(|_| check(af_memory_manager_set_user_lock_fn(handle, user_lock_cb)))
+            .and_then(|_| check(af_memory_manager_set_user_unlock_fn(handle, user_unlock_cb)))
+            .and_then(|_| {
+                check(af_memory_manager_set_is_user_locked_fn(
+                    handle,
+                    is_user_locked_cb,
+                ))
+            })
+            .and_then(|_| {
+                check(af_memory_manager_set_get_memory_pressure_fn(
+                    handle,
+                    get_memory_pressure_cb,
+                ))
+            })
+            .and_then(|_| {
+                check(af_memory_manager_set_jit_tree_exceeds_memory_pressure_fn(
+                    handle,
+                    jit_tree_exceeds_memory_pressure_cb,
+                ))
+            })
+            .and_then(|_| {
+                check(af_memory_manager_set_add_memory_management_fn(
+                    handle,
+                    add_memory_management_cb,
+                ))
+            })
+            .and_then(|_| {
+                check(af_memory_manager_set_remove_memory_management_fn(
+                    handle,
+                    remove_memory_management_cb,
+                ))
+            })
+            .and_then(|_| check(af_set_memory_manager(handle)))
+    };
+    if let Err(err) = setup {
+        unsafe {
+            af_release_memory_manager(handle);
+            drop(Box::from_raw(payload));
+        }
+        return Err(err);
+    }
+
+    let backend = active_backend_id();
+    release_installed(backend)?;
+    let mut installed = INSTALLED.lock().unwrap_or_else(|err| err.into_inner());
+    installed.insert(backend, Installed { handle, payload });
+    Ok(())
+}
+
+/// Restore the default memory manager of the active backend
+///
+/// The memory manager installed using [set_memory_manager](./fn.set_memory_manager.html) is
+/// shut down and dropped. Its buffers that are still locked by live Arrays are not freed and
+/// hence leak.
+pub fn unset_memory_manager() -> AfResult<()> {
+    check(unsafe { af_unset_memory_manager() })?;
+    release_installed(active_backend_id())
+}
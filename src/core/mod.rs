@@ -6,6 +6,12 @@ mod arith;
 pub use array::*;
 mod array;
 
+pub use array_file::ArrayFile;
+mod array_file;
+
+#[cfg(feature = "afserde")]
+pub mod array_bytes;
+
 pub use backend::*;
 mod backend;
 
@@ -14,6 +20,21 @@ pub use data::*;
 #[cfg(feature = "data")]
 mod data;
 
+#[cfg(all(
+    feature = "algorithm",
+    feature = "arithmetic",
+    feature = "data",
+    feature = "indexing"
+))]
+pub use compare::{array_equal, close_mismatch, CloseMismatch};
+#[cfg(all(
+    feature = "algorithm",
+    feature = "arithmetic",
+    feature = "data",
+    feature = "indexing"
+))]
+mod compare;
+
 pub use defines::*;
 mod defines;
 
@@ -29,14 +50,33 @@ mod error;
 pub use event::*;
 mod event;
 
+pub use executor::{AfExecutor, AfTask};
+mod executor;
+
 #[cfg(feature = "indexing")]
 pub use index::*;
 #[cfg(feature = "indexing")]
 mod index;
 
+#[cfg(feature = "indexing")]
+pub use iter::Chunks;
+pub use iter::CollectArray;
+mod iter;
+
+pub use literal::ArrayLiteral;
+mod literal;
+
 #[cfg(feature = "macros")]
 mod macros;
 
+pub use memory::*;
+mod memory;
+
+#[cfg(feature = "afnpy")]
+pub use npy::*;
+#[cfg(feature = "afnpy")]
+mod npy;
+
 #[cfg(feature = "random")]
 pub use random::*;
 #[cfg(feature = "random")]
@@ -47,5 +87,13 @@ pub use seq::Seq;
 #[cfg(feature = "indexing")]
 mod seq;
 
+pub use transfer::HostTransfer;
+mod transfer;
+
 pub use util::*;
 mod util;
+
+#[cfg(feature = "indexing")]
+pub use view::ArrayViewMut;
+#[cfg(feature = "indexing")]
+mod view;
@@ -0,0 +1,615 @@
+use super::array::Array;
+use super::defines::{AfError, DType};
+use super::dim4::Dim4;
+use super::error::{AfResult, ArrayFireError};
+use super::util::HasAfEnum;
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Seek, Write};
+use std::path::Path;
+use zip::result::ZipError;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+const HEADER_ALIGNMENT: usize = 64;
+
+/// Byte order marker of multi-byte types written by this machine
+const NATIVE_ORDER: char = if cfg!(target_endian = "little") {
+    '<'
+} else {
+    '>'
+};
+
+/// Parsed header of an npy file
+struct NpyHeader {
+    dtype: DType,
+    fortran_order: bool,
+    shape: Dim4,
+    swap_bytes: bool,
+}
+
+fn io_error(err: io::Error) -> ArrayFireError {
+    ArrayFireError::with_message(AfError::ERR_RUNTIME, err.to_string())
+}
+
+fn zip_error(err: ZipError) -> ArrayFireError {
+    match err {
+        ZipError::Io(err) => io_error(err),
+        ZipError::FileNotFound => {
+            ArrayFireError::with_message(AfError::ERR_ARG, "array not found in npz archive")
+        }
+        _ => ArrayFireError::with_message(AfError::ERR_RUNTIME, err.to_string()),
+    }
+}
+
+fn format_error<S: Into<String>>(message: S) -> ArrayFireError {
+    ArrayFireError::with_message(AfError::ERR_ARG, message)
+}
+
+/// Returns the type character and size in bytes of `dtype` in npy type descriptors
+fn type_code(dtype: DType) -> (char, usize) {
+    match dtype {
+        DType::F32 => ('f', 4),
+        DType::C32 => ('c', 8),
+        DType::F64 => ('f', 8),
+        DType::C64 => ('c', 16),
+        DType::B8 => ('b', 1),
+        DType::S32 => ('i', 4),
+        DType::U32 => ('u', 4),
+        DType::U8 => ('u', 1),
+        DType::S64 => ('i', 8),
+        DType::U64 => ('u', 8),
+        DType::S16 => ('i', 2),
+        DType::U16 => ('u', 2),
+        DType::F16 => ('f', 2),
+    }
+}
+
+fn type_descr(dtype: DType) -> String {
+    let (code, size) = type_code(dtype);
+    let order = if size == 1 { '|' } else { NATIVE_ORDER };
+    format!("{}{}{}", order, code, size)
+}
+
+/// Returns the data type along with whether elements need their bytes swapped
+fn parse_descr(descr: &str) -> AfResult<(DType, bool)> {
+    let unsupported = || {
+        ArrayFireError::with_message(
+            AfError::ERR_NOT_SUPPORTED,
+            format!("npy data type '{}' is not supported", descr),
+        )
+    };
+    let mut chars = descr.chars();
+    let order = chars.next().ok_or_else(unsupported)?;
+    let code = chars.next().ok_or_else(unsupported)?;
+    let size: usize = chars.as_str().parse().map_err(|_| unsupported())?;
+    let dtype = match (code, size) {
+        ('f', 4) => DType::F32,
+        ('c', 8) => DType::C32,
+        ('f', 8) => DType::F64,
+        ('c', 16) => DType::C64,
+        ('b', 1) => DType::B8,
+        ('i', 4) => DType::S32,
+        ('u', 4) => DType::U32,
+        ('u', 1) => DType::U8,
+        ('i', 8) => DType::S64,
+        ('u', 8) => DType::U64,
+        ('i', 2) => DType::S16,
+        ('u', 2) => DType::U16,
+        ('f', 2) => DType::F16,
+        _ => return Err(unsupported()),
+    };
+    let swap_bytes = match order {
+        '|' | '=' => false,
+        '<' | '>' => size > 1 && order != NATIVE_ORDER,
+        _ => return Err(unsupported()),
+    };
+    Ok((dtype, swap_bytes))
+}
+
+/// Returns the text following `'key':` in the header dictionary
+fn header_value<'a>(header: &'a str, key: &str) -> AfResult<&'a str> {
+    let pattern = format!("'{}':", key);
+    header
+        .find(&pattern)
+        .map(|pos| header[pos + pattern.len()..].trim_start())
+        .ok_or_else(|| format_error(format!("npy header is missing '{}'", key)))
+}
+
+fn parse_header(header: &str) -> AfResult<NpyHeader> {
+    let descr = header_value(header, "descr")?;
+    let descr = descr
+        .strip_prefix('\'')
+        .and_then(|rest| rest.split('\'').next())
+        .ok_or_else(|| format_error("invalid 'descr' in npy header"))?;
+    let (dtype, swap_bytes) = parse_descr(descr)?;
+
+    let fortran_order = header_value(header, "fortran_order")?.starts_with("True");
+
+    let shape = header_value(header, "shape")?;
+    let shape = shape
+        .strip_prefix('(')
+        .and_then(|rest| rest.split(')').next())
+        .ok_or_else(|| format_error("invalid 'shape' in npy header"))?;
+    let shape = shape
+        .split(',')
+        .map(str::trim)
+        .filter(|len| !len.is_empty())
+        .map(|len| len.parse::<u64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| format_error("invalid 'shape' in npy header"))?;
+    if shape.len() > 4 {
+        return Err(ArrayFireError::with_message(
+            AfError::ERR_NOT_SUPPORTED,
+            format!(
+                "npy array with {} dimensions can not be loaded, ArrayFire supports at most 4",
+                shape.len()
+            ),
+        ));
+    }
+    let mut dims = [1u64; 4];
+    dims[..shape.len()].copy_from_slice(&shape);
+
+    Ok(NpyHeader {
+        dtype,
+        fortran_order,
+        shape: Dim4::new(&dims),
+        swap_bytes,
+    })
+}
+
+fn read_header<R: Read>(reader: &mut R) -> AfResult<NpyHeader> {
+    let mut preamble = [0u8; 8];
+    reader.read_exact(&mut preamble).map_err(io_error)?;
+    if &preamble[..6] != MAGIC {
+        return Err(format_error("not an npy file"));
+    }
+    let header_len = match preamble[6] {
+        1 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len).map_err(io_error)?;
+            u16::from_le_bytes(len) as usize
+        }
+        2 | 3 => {
+            let mut len = [0u8; 4];
+            reader.read_exact(&mut len).map_err(io_error)?;
+            u32::from_le_bytes(len) as usize
+        }
+        version => {
+            return Err(ArrayFireError::with_message(
+                AfError::ERR_NOT_SUPPORTED,
+                format!("npy format version {} is not supported", version),
+            ))
+        }
+    };
+    let mut header = vec![0u8; header_len];
+    reader.read_exact(&mut header).map_err(io_error)?;
+    let header = String::from_utf8(header).map_err(|_| format_error("invalid npy header"))?;
+    parse_header(&header)
+}
+
+fn write_header<W: Write>(writer: &mut W, dtype: DType, dims: Dim4) -> io::Result<()> {
+    // Trailing unit dimensions are dropped, leaving at least one dimension
+    let used = dims
+        .get()
+        .iter()
+        .rposition(|&d| d != 1)
+        .map_or(1, |pos| pos + 1);
+    let shape = match used {
+        1 => format!("({},)", dims[0]),
+        _ => {
+            let lens: Vec<String> = dims.get()[..used].iter().map(u64::to_string).collect();
+            format!("({})", lens.join(", "))
+        }
+    };
+    let mut header = format!(
+        "{{'descr': '{}', 'fortran_order': True, 'shape': {}, }}",
+        type_descr(dtype),
+        shape
+    );
+
+    // Version 1.0 stores header length in two bytes, version 2.0 in four
+    let (version, len_bytes) = if header.len() + 12 < u16::MAX as usize {
+        (1u8, 2)
+    } else {
+        (2u8, 4)
+    };
+    let preamble_len = MAGIC.len() + 2 + len_bytes;
+    let total = preamble_len + header.len() + 1;
+    let padding = (HEADER_ALIGNMENT - total % HEADER_ALIGNMENT) % HEADER_ALIGNMENT;
+    header.push_str(&" ".repeat(padding));
+    header.push('\n');
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[version, 0])?;
+    if version == 1 {
+        writer.write_all(&(header.len() as u16).to_le_bytes())?;
+    } else {
+        writer.write_all(&(header.len() as u32).to_le_bytes())?;
+    }
+    writer.write_all(header.as_bytes())
+}
+
+/// Reads an array, `limit` is the length of the stream in bytes if it is known
+fn read_data<T: HasAfEnum, R: Read>(reader: &mut R, limit: Option<u64>) -> AfResult<Array<T>> {
+    let header = read_header(reader)?;
+    let expected = T::get_af_dtype();
+    if header.dtype != expected {
+        return Err(ArrayFireError::with_message(
+            AfError::ERR_TYPE,
+            format!(
+                "npy data type is {:?}, requested type is {:?}",
+                header.dtype, expected
+            ),
+        ));
+    }
+
+    let (code, size) = type_code(header.dtype);
+    let nbytes = header
+        .shape
+        .get()
+        .iter()
+        .try_fold(size as u64, |acc, &dim| acc.checked_mul(dim))
+        .filter(|&nbytes| match limit {
+            Some(limit) => nbytes <= limit,
+            None => true,
+        })
+        .ok_or_else(|| {
+            ArrayFireError::with_message(
+                AfError::ERR_SIZE,
+                format!("npy data of shape {} exceeds the input", header.shape),
+            )
+        })?;
+    // Buffer grows with the data actually read, a corrupt header can't cause a huge allocation
+    let mut bytes = Vec::new();
+    reader
+        .take(nbytes)
+        .read_to_end(&mut bytes)
+        .map_err(io_error)?;
+    if bytes.len() as u64 != nbytes {
+        return Err(ArrayFireError::with_message(
+            AfError::ERR_SIZE,
+            format!(
+                "npy data is {} bytes long, expected {} bytes",
+                bytes.len(),
+                nbytes
+            ),
+        ));
+    }
+    let elements = bytes.len() / size;
+    if header.swap_bytes {
+        // Real and imaginary parts of complex numbers are swapped individually
+        let part = if code == 'c' { size / 2 } else { size };
+        for value in bytes.chunks_exact_mut(part) {
+            value.reverse();
+        }
+    }
+    if header.dtype == DType::B8 {
+        for value in bytes.iter_mut() {
+            *value = (*value != 0) as u8;
+        }
+    }
+
+    let mut data = vec![T::default(); elements];
+    // Sizes match as `T` corresponds to `header.dtype`, byte values are valid for `T`
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), data.as_mut_ptr() as *mut u8, bytes.len());
+    }
+
+    if header.fortran_order {
+        Ok(Array::new(&data, header.shape))
+    } else {
+        Ok(Array::new(&c_to_fortran(&data, header.shape), header.shape))
+    }
+}
+
+/// Reorders elements stored in row major order into column major order
+fn c_to_fortran<T: Clone>(data: &[T], dims: Dim4) -> Vec<T> {
+    let [d0, d1, d2, d3] = *dims.get();
+    let mut out = Vec::with_capacity(data.len());
+    for l in 0..d3 {
+        for k in 0..d2 {
+            for j in 0..d1 {
+                for i in 0..d0 {
+                    let index = ((i * d1 + j) * d2 + k) * d3 + l;
+                    out.push(data[index as usize].clone());
+                }
+            }
+        }
+    }
+    out
+}
+
+fn write_data<T: HasAfEnum, W: Write>(array: &Array<T>, writer: &mut W) -> AfResult<()> {
+    let mut data = vec![T::default(); array.elements()];
+    array.host(&mut data);
+    // Elements are plain values, viewing them as bytes is valid
+    let bytes = unsafe {
+        std::slice::from_raw_parts(
+            data.as_ptr() as *const u8,
+            data.len() * std::mem::size_of::<T>(),
+        )
+    };
+    write_header(writer, T::get_af_dtype(), array.dims()).map_err(io_error)?;
+    writer.write_all(bytes).map_err(io_error)
+}
+
+impl<T: HasAfEnum> Array<T> {
+    /// Reads an Array stored in NumPy's npy format
+    ///
+    /// Both Fortran (column major) and C (row major) ordered data are supported. In either
+    /// case, element at index `[i, j, k, l]` of the NumPy array is found at position
+    /// `(i, j, k, l)` of the returned Array. An error is returned if the stored data type
+    /// doesn't correspond to `T` or the stored array has more than four dimensions.
+    pub fn read_npy<R: Read>(mut reader: R) -> AfResult<Self> {
+        read_data(&mut reader, None)
+    }
+
+    /// Writes the Array in NumPy's npy format
+    ///
+    /// Data is stored in Fortran order, trailing dimensions of unit length are not stored.
+    pub fn write_npy<W: Write>(&self, mut writer: W) -> AfResult<()> {
+        write_data(self, &mut writer)?;
+        writer.flush().map_err(io_error)
+    }
+
+    /// Loads an Array from npy file at `path`
+    ///
+    /// See [read_npy](./struct.Array.html#method.read_npy) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use arrayfire::Array;
+    ///
+    /// let weights = Array::<f32>::load_npy("weights.npy").unwrap();
+    /// ```
+    pub fn load_npy<P: AsRef<Path>>(path: P) -> AfResult<Self> {
+        let file = File::open(path).map_err(io_error)?;
+        let len = file.metadata().map_err(io_error)?.len();
+        read_data(&mut BufReader::new(file), Some(len))
+    }
+
+    /// Saves the Array to npy file at `path`
+    ///
+    /// See [write_npy](./struct.Array.html#method.write_npy) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use arrayfire::{randu, Dim4};
+    ///
+    /// let a = randu::<f32>(Dim4::new(&[3, 4, 1, 1]));
+    /// a.save_npy("a.npy").unwrap();
+    /// ```
+    pub fn save_npy<P: AsRef<Path>>(&self, path: P) -> AfResult<()> {
+        let file = File::create(path).map_err(io_error)?;
+        self.write_npy(BufWriter::new(file))
+    }
+}
+
+/// Writer of NumPy's npz archives holding multiple named arrays
+///
+/// Arrays are stored without compression, same as `numpy.savez`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use arrayfire::{randu, Dim4, NpzWriter};
+///
+/// let mut npz = NpzWriter::create("model.npz").unwrap();
+/// npz.add("weights", &randu::<f32>(Dim4::new(&[3, 4, 1, 1]))).unwrap();
+/// npz.add("steps", &randu::<u32>(Dim4::new(&[1, 1, 1, 1]))).unwrap();
+/// npz.finish().unwrap();
+/// ```
+pub struct NpzWriter<W: Write + Seek = BufWriter<File>> {
+    zip: ZipWriter<W>,
+}
+
+impl NpzWriter {
+    /// Creates a new archive at `path`
+    pub fn create<P: AsRef<Path>>(path: P) -> AfResult<Self> {
+        let file = File::create(path).map_err(io_error)?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write + Seek> NpzWriter<W> {
+    /// Creates a new archive written to `writer`
+    pub fn new(writer: W) -> Self {
+        Self {
+            zip: ZipWriter::new(writer),
+        }
+    }
+
+    /// Adds `array` to the archive under `name`
+    pub fn add<T: HasAfEnum>(&mut self, name: &str, array: &Array<T>) -> AfResult<()> {
+        let options = FileOptions::default().compression_method(CompressionMethod::Stored);
+        self.zip
+            .start_file(format!("{}.npy", name), options)
+            .map_err(zip_error)?;
+        write_data(array, &mut self.zip)
+    }
+
+    /// Completes the archive and returns the underlying writer
+    pub fn finish(mut self) -> AfResult<W> {
+        self.zip.finish().map_err(zip_error)
+    }
+}
+
+/// Reader of NumPy's npz archives holding multiple named arrays
+///
+/// Archives written by both `numpy.savez` and `numpy.savez_compressed` are supported.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use arrayfire::NpzReader;
+///
+/// let mut npz = NpzReader::open("model.npz").unwrap();
+/// for name in npz.names() {
+///     println!("{}: {:?}", name, npz.dtype(&name).unwrap());
+/// }
+/// let weights = npz.read::<f32>("weights").unwrap();
+/// ```
+pub struct NpzReader<R: Read + Seek = BufReader<File>> {
+    zip: ZipArchive<R>,
+}
+
+impl NpzReader {
+    /// Opens the archive at `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> AfResult<Self> {
+        let file = File::open(path).map_err(io_error)?;
+        Self::new(BufReader::new(file))
+    }
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+    /// Opens the archive read from `reader`
+    pub fn new(reader: R) -> AfResult<Self> {
+        Ok(Self {
+            zip: ZipArchive::new(reader).map_err(zip_error)?,
+        })
+    }
+
+    /// Returns names of the arrays in the archive
+    pub fn names(&self) -> Vec<String> {
+        self.zip
+            .file_names()
+            .map(|name| name.strip_suffix(".npy").unwrap_or(name).to_string())
+            .collect()
+    }
+
+    /// Returns the data type of array `name` without reading its data
+    pub fn dtype(&mut self, name: &str) -> AfResult<DType> {
+        let mut file = self
+            .zip
+            .by_name(&format!("{}.npy", name))
+            .map_err(zip_error)?;
+        Ok(read_header(&mut file)?.dtype)
+    }
+
+    /// Reads array `name` from the archive
+    ///
+    /// See [Array::read_npy](./struct.Array.html#method.read_npy) for details.
+    pub fn read<T: HasAfEnum>(&mut self, name: &str) -> AfResult<Array<T>> {
+        let mut file = self
+            .zip
+            .by_name(&format!("{}.npy", name))
+            .map_err(zip_error)?;
+        let len = file.size();
+        read_data(&mut file, Some(len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::array::Array;
+    use super::super::defines::{AfError, DType};
+    use super::super::device::set_device;
+    use super::{NpzReader, NpzWriter};
+    use crate::dim4;
+    use num::Complex;
+    use std::io::Cursor;
+
+    fn to_vec<T: crate::HasAfEnum>(array: &Array<T>) -> Vec<T> {
+        let mut data = vec![T::default(); array.elements()];
+        array.host(&mut data);
+        data
+    }
+
+    #[test]
+    fn npy_roundtrip() {
+        set_device(0);
+        let values: Vec<f32> = (0..24).map(|v| v as f32).collect();
+        let input = Array::new(&values, dim4!(2, 3, 4));
+        let mut buffer = Vec::new();
+        input.write_npy(&mut buffer).unwrap();
+        assert_eq!((buffer.len() - 24 * 4) % 64, 0);
+
+        let output = Array::<f32>::read_npy(buffer.as_slice()).unwrap();
+        assert_eq!(output.dims(), dim4!(2, 3, 4));
+        assert_eq!(to_vec(&output), values);
+
+        let complex = Array::new(
+            &[Complex::new(1.0f64, -1.0), Complex::new(2.0, 0.5)],
+            dim4!(2),
+        );
+        let mut buffer = Vec::new();
+        complex.write_npy(&mut buffer).unwrap();
+        let output = Array::<Complex<f64>>::read_npy(buffer.as_slice()).unwrap();
+        assert_eq!(to_vec(&output), to_vec(&complex));
+
+        let err = Array::<f32>::read_npy(buffer.as_slice()).unwrap_err();
+        assert_eq!(err.code(), AfError::ERR_TYPE);
+    }
+
+    #[test]
+    fn npy_c_order_and_big_endian() {
+        set_device(0);
+        // Equivalent of np.array([[1, 2, 3], [4, 5, 6]], dtype='>i2')
+        let header = "{'descr': '>i2', 'fortran_order': False, 'shape': (2, 3), }";
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        for value in 1i16..=6 {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        }
+
+        let output = Array::<i16>::read_npy(bytes.as_slice()).unwrap();
+        assert_eq!(output.dims(), dim4!(2, 3));
+        assert_eq!(to_vec(&output), vec![1, 4, 2, 5, 3, 6]);
+    }
+
+    #[test]
+    fn npy_rejects_truncated_data() {
+        set_device(0);
+        let npy = |shape: &str, data: &[u8]| {
+            let header = format!(
+                "{{'descr': '<i2', 'fortran_order': True, 'shape': {}, }}",
+                shape
+            );
+            let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+            bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+            bytes.extend_from_slice(header.as_bytes());
+            bytes.extend_from_slice(data);
+            bytes
+        };
+
+        let truncated = npy("(2, 3)", &[0; 4]);
+        let err = Array::<i16>::read_npy(truncated.as_slice()).unwrap_err();
+        assert_eq!(err.code(), AfError::ERR_SIZE);
+
+        let huge = npy("(1099511627776, 1073741824)", &[0; 4]);
+        let err = Array::<i16>::read_npy(huge.as_slice()).unwrap_err();
+        assert_eq!(err.code(), AfError::ERR_SIZE);
+    }
+
+    #[test]
+    fn npz_roundtrip() {
+        set_device(0);
+        let flags = Array::new(&[true, false, true], dim4!(3));
+        let values = Array::new(&[half::f16::from_f32(0.5); 4], dim4!(2, 2));
+
+        let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+        npz.add("flags", &flags).unwrap();
+        npz.add("values", &values).unwrap();
+        let buffer = npz.finish().unwrap();
+
+        let mut npz = NpzReader::new(buffer).unwrap();
+        let mut names = npz.names();
+        names.sort();
+        assert_eq!(names, vec!["flags", "values"]);
+        assert_eq!(npz.dtype("values").unwrap(), DType::F16);
+        assert_eq!(
+            to_vec(&npz.read::<bool>("flags").unwrap()),
+            vec![true, false, true]
+        );
+        assert_eq!(
+            to_vec(&npz.read::<half::f16>("values").unwrap()),
+            to_vec(&values)
+        );
+        assert!(npz.read::<f32>("missing").is_err());
+    }
+}
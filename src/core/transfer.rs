@@ -0,0 +1,219 @@
+use super::array::Array;
+use super::backend::{activate, get_active_backend};
+use super::defines::AfError;
+use super::error::{af_result, try_af, AfResult, ArrayFireError};
+use super::event::Event;
+use super::util::{af_array, dim_t, void_ptr, HasAfEnum};
+
+use libc::{c_int, c_void};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+extern "C" {
+    fn af_get_data_ptr(data: *mut c_void, arr: af_array) -> c_int;
+    fn af_alloc_pinned(non_pagable_ptr: *mut void_ptr, bytes: dim_t) -> c_int;
+    fn af_free_pinned(non_pagable_ptr: void_ptr) -> c_int;
+}
+
+/// Destination of a transfer, page locked when the backend allows it
+enum HostBuffer<T> {
+    Pinned(*mut T),
+    Pageable(Vec<T>),
+}
+
+impl<T> HostBuffer<T> {
+    fn new(len: usize) -> Self {
+        if len == 0 {
+            return HostBuffer::Pageable(Vec::new());
+        }
+        let mut ptr: void_ptr = std::ptr::null_mut();
+        let bytes = (len * std::mem::size_of::<T>()) as dim_t;
+        let err_val = unsafe { af_alloc_pinned(&mut ptr as *mut void_ptr, bytes) };
+        if err_val == 0 && !ptr.is_null() {
+            HostBuffer::Pinned(ptr as *mut T)
+        } else {
+            HostBuffer::Pageable(Vec::with_capacity(len))
+        }
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut T {
+        match self {
+            HostBuffer::Pinned(ptr) => *ptr,
+            HostBuffer::Pageable(data) => data.as_mut_ptr(),
+        }
+    }
+}
+
+impl<T> Drop for HostBuffer<T> {
+    fn drop(&mut self) {
+        if let HostBuffer::Pinned(ptr) = self {
+            // Failing to release page locked memory leaks it, which is not worth a panic
+            let _ = unsafe { af_free_pinned(*ptr as void_ptr) };
+        }
+    }
+}
+
+/// Address of the destination buffer, which outlives the worker thread writing to it
+struct Destination(usize);
+
+/// Handle to an asynchronous copy of an Array to host memory
+///
+/// Created by [Array::host_async](./struct.Array.html#method.host_async). The transfer owns the
+/// destination buffer, which is page locked memory from
+/// [alloc_pinned](./fn.alloc_pinned.html) when the backend provides it. An
+/// [Event](./struct.Event.html) is marked on the calling thread's queue when the transfer is
+/// created, and a worker thread copies the data once all work enqueued before the mark is
+/// complete. The calling thread is free to enqueue more work in the meantime.
+///
+/// Dropping the handle waits for the copy to finish.
+///
+/// # Examples
+///
+/// ```rust
+/// use arrayfire::{randu, Dim4};
+/// let batches: Vec<_> = (0..3).map(|_| randu::<f32>(Dim4::new(&[16, 16, 1, 1]))).collect();
+///
+/// let mut pending = None;
+/// for batch in &batches {
+///     let result = batch * 2.0f32;
+///     // Download the previous result while the current one is computed
+///     if let Some(mut transfer) = pending.replace(result.host_async()) {
+///         let data: &[f32] = transfer.wait().unwrap();
+///         assert_eq!(data.len(), 256);
+///     }
+/// }
+/// ```
+pub struct HostTransfer<T: HasAfEnum> {
+    buffer: HostBuffer<T>,
+    len: usize,
+    ready: Arc<AtomicBool>,
+    worker: Option<JoinHandle<AfResult<()>>>,
+    status: Option<AfResult<()>>,
+}
+
+// The worker thread only ever holds the address of the buffer
+unsafe impl<T: HasAfEnum> Send for HostTransfer<T> {}
+
+impl<T: HasAfEnum> Array<T> {
+    /// Start copying the Array to host memory without blocking the calling thread
+    ///
+    /// # Return Values
+    ///
+    /// A [HostTransfer](./struct.HostTransfer.html) that owns the destination buffer. Use
+    /// [is_ready](./struct.HostTransfer.html#method.is_ready) to poll it and
+    /// [wait](./struct.HostTransfer.html#method.wait) to access the data.
+    pub fn host_async(&self) -> HostTransfer<T>
+    where
+        T: 'static,
+    {
+        let len = self.elements();
+        let mut buffer = HostBuffer::new(len);
+        let ready = Arc::new(AtomicBool::new(false));
+
+        self.eval();
+        let event = Event::default();
+        event.mark();
+
+        let array = self.clone();
+        // Worker thread starts on the default backend, hence both are made active there
+        let backend = get_active_backend();
+        let device = self.get_device_id();
+        let destination = Destination(buffer.as_mut_ptr() as usize);
+        let flag = ready.clone();
+        let worker = thread::spawn(move || {
+            let result = if len == 0 {
+                Ok(())
+            } else {
+                activate(backend, device)
+                    .and_then(|_| try_af(|| event.block()))
+                    .and_then(|_| {
+                        let err_val =
+                            unsafe { af_get_data_ptr(destination.0 as *mut c_void, array.get()) };
+                        af_result(AfError::from(err_val))
+                    })
+            };
+            flag.store(true, Ordering::Release);
+            result
+        });
+
+        HostTransfer {
+            buffer,
+            len,
+            ready,
+            worker: Some(worker),
+            status: None,
+        }
+    }
+}
+
+impl<T: HasAfEnum> HostTransfer<T> {
+    /// Check if the copy has finished, without blocking
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    /// Block until the copy has finished
+    ///
+    /// # Return Values
+    ///
+    /// The host copy of the Array's data in column major order, or the error raised while
+    /// copying it.
+    pub fn wait(&mut self) -> AfResult<&[T]> {
+        if let Some(worker) = self.worker.take() {
+            let status = worker.join().unwrap_or_else(|_| {
+                Err(ArrayFireError::with_message(
+                    AfError::ERR_INTERNAL,
+                    String::from("host transfer worker panicked"),
+                ))
+            });
+            if status.is_ok() {
+                if let HostBuffer::Pageable(data) = &mut self.buffer {
+                    // The worker initialized all `len` elements of the allocation
+                    unsafe { data.set_len(self.len) };
+                }
+            }
+            self.status = Some(status);
+        }
+        match &self.status {
+            Some(Ok(())) => Ok(match &self.buffer {
+                HostBuffer::Pinned(ptr) => unsafe { std::slice::from_raw_parts(*ptr, self.len) },
+                HostBuffer::Pageable(data) => data.as_slice(),
+            }),
+            Some(Err(err)) => Err(err.clone()),
+            None => unreachable!(),
+        }
+    }
+}
+
+impl<T: HasAfEnum> Drop for HostTransfer<T> {
+    fn drop(&mut self) {
+        // The worker may still be writing to the buffer
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::array::Array;
+    use super::super::device::set_device;
+    use crate::dim4;
+
+    #[test]
+    fn host_async_copies_array() {
+        set_device(0);
+        let values: Vec<f32> = (0..64).map(|v| v as f32).collect();
+        let a = Array::new(&values, dim4!(8, 8));
+        let mut transfer = a.host_async();
+        drop(a);
+        assert_eq!(transfer.wait().unwrap(), &values[..]);
+        assert!(transfer.is_ready());
+        assert_eq!(transfer.wait().unwrap().len(), 64);
+
+        let empty = Array::<i32>::new_empty(dim4!(0));
+        let mut transfer = empty.host_async();
+        assert!(transfer.wait().unwrap().is_empty());
+    }
+}
@@ -34,6 +34,8 @@ pub type af_features = *const libc::c_void;
 pub type af_random_engine = *mut libc::c_void;
 /// ArrayFire FFI Type alias for af_window
 pub type af_window = *mut libc::c_void;
+/// ArrayFire FFI Type alias for af_memory_manager
+pub type af_memory_manager = *mut libc::c_void;
 
 extern "C" {
     fn af_get_size_of(size: *mut size_t, aftype: c_uint) -> c_int;
@@ -75,7 +77,7 @@ impl From<i32> for AfError {
 
 impl From<u32> for DType {
     fn from(t: u32) -> Self {
-        assert!(DType::F32 as u32 <= t && t <= DType::U64 as u32);
+        assert!(DType::F32 as u32 <= t && t <= DType::F16 as u32);
         unsafe { mem::transmute(t) }
     }
 }
@@ -0,0 +1,184 @@
+use super::array::Array;
+use super::defines::AfError;
+use super::error::{AfResult, ArrayFireError};
+use super::index::{assign_gen, index_gen_ref, ArrayIndex, Indexer};
+use super::util::HasAfEnum;
+
+#[cfg(feature = "arithmetic")]
+use super::arith::{add, div, mul, sub, Convertable};
+#[cfg(feature = "data")]
+use super::data::{constant, ConstGenerator};
+#[cfg(feature = "arithmetic")]
+use super::util::ImplicitPromote;
+
+use std::ops::Deref;
+#[cfg(feature = "arithmetic")]
+use std::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+/// Mutable view of a part of an Array
+///
+/// The view is created using [Array::view_mut](./struct.Array.html#method.view_mut) and
+/// mutably borrows the parent Array. Reading the view, through `Deref` to
+/// [Array](./struct.Array.html), returns the selected elements. Compound assignments,
+/// [fill](./struct.ArrayViewMut.html#method.fill) and
+/// [assign](./struct.ArrayViewMut.html#method.assign) update the view lazily; the result is
+/// written back into the parent with a single [assign_gen](./fn.assign_gen.html) call once
+/// the view is dropped.
+///
+/// # Examples
+///
+/// ```rust
+/// use arrayfire::{constant, Dim4};
+/// let mut a = constant(1.0f32, Dim4::new(&[4, 4, 1, 1]));
+/// {
+///     let mut top = a.view_mut((0..2, ..)).unwrap();
+///     top *= 3.0f32;
+///     top += 1.0f32;
+/// }
+/// a.view_mut((.., 3)).unwrap().fill(0.0);
+///
+/// let mut host = vec![0.0f32; 16];
+/// a.host(&mut host);
+/// assert_eq!(&host[..4], &[4.0, 4.0, 1.0, 1.0]);
+/// assert_eq!(&host[12..], &[0.0; 4]);
+/// ```
+pub struct ArrayViewMut<'a, T: HasAfEnum> {
+    parent: &'a mut Array<T>,
+    indexer: Indexer<'a>,
+    values: Array<T>,
+    modified: bool,
+}
+
+impl<T: HasAfEnum> Array<T> {
+    /// Create a mutable view of the part of the Array selected by `indices`
+    ///
+    /// `indices` follows the conventions of [Array::i](./struct.Array.html#method.i).
+    ///
+    /// # Return Values
+    ///
+    /// The view. Fails with `ERR_SIZE` if a range or integer is out of bounds.
+    pub fn view_mut<'a, I>(&'a mut self, indices: I) -> AfResult<ArrayViewMut<'a, T>>
+    where
+        I: ArrayIndex + 'a,
+    {
+        let mut indexer = Indexer::default();
+        let dims = indices.set_indices(&mut indexer, self.dims())?;
+        let values = if dims.elements() == 0 {
+            Array::new_empty(dims)
+        } else {
+            index_gen_ref(self, &indexer)
+        };
+        Ok(ArrayViewMut {
+            parent: self,
+            indexer,
+            values,
+            modified: false,
+        })
+    }
+}
+
+impl<'a, T: HasAfEnum> ArrayViewMut<'a, T> {
+    /// Replace the elements of the view with `values`
+    ///
+    /// Fails with `ERR_SIZE` if `values` doesn't have the dimensions of the view.
+    pub fn assign(&mut self, values: &Array<T>) -> AfResult<()> {
+        if values.dims() != self.values.dims() {
+            return Err(ArrayFireError::with_message(
+                AfError::ERR_SIZE,
+                format!(
+                    "cannot assign an Array of dims {} to a view of dims {}",
+                    values.dims(),
+                    self.values.dims()
+                ),
+            ));
+        }
+        self.values = values.clone();
+        self.modified = true;
+        Ok(())
+    }
+
+    /// Set all elements of the view to `value`
+    #[cfg(feature = "data")]
+    pub fn fill(&mut self, value: T)
+    where
+        T: ConstGenerator<OutType = T>,
+    {
+        self.values = constant(value, self.values.dims());
+        self.modified = true;
+    }
+}
+
+impl<'a, T: HasAfEnum> Deref for ArrayViewMut<'a, T> {
+    type Target = Array<T>;
+
+    fn deref(&self) -> &Array<T> {
+        &self.values
+    }
+}
+
+impl<'a, T: HasAfEnum> Drop for ArrayViewMut<'a, T> {
+    fn drop(&mut self) {
+        if self.modified && self.values.elements() > 0 {
+            assign_gen(self.parent, &self.indexer, &self.values);
+        }
+    }
+}
+
+#[cfg(feature = "arithmetic")]
+macro_rules! view_assign_func {
+    ($op_name: ident, $fn_name: ident, $func: ident) => {
+        impl<'a, T, U> $op_name<U> for ArrayViewMut<'a, T>
+        where
+            T: HasAfEnum + ImplicitPromote<U::OutType>,
+            U: Convertable,
+            U::OutType: ImplicitPromote<T>,
+        {
+            fn $fn_name(&mut self, rhs: U) {
+                self.values = $func(&self.values, &rhs, false).cast::<T>();
+                self.modified = true;
+            }
+        }
+    };
+}
+
+#[cfg(feature = "arithmetic")]
+view_assign_func!(AddAssign, add_assign, add);
+#[cfg(feature = "arithmetic")]
+view_assign_func!(SubAssign, sub_assign, sub);
+#[cfg(feature = "arithmetic")]
+view_assign_func!(MulAssign, mul_assign, mul);
+#[cfg(feature = "arithmetic")]
+view_assign_func!(DivAssign, div_assign, div);
+
+#[cfg(test)]
+mod tests {
+    use super::super::array::Array;
+    use super::super::device::set_device;
+    use crate::dim4;
+
+    #[test]
+    fn view_mut_writes_back_on_drop() {
+        set_device(0);
+        let mut a = Array::new(&[1i32, 2, 3, 4, 5, 6], dim4!(3, 2));
+        {
+            let mut view = a.view_mut((1.., 1)).unwrap();
+            assert_eq!(view.dims(), dim4!(2, 1));
+            view *= 10;
+            view += Array::new(&[1i32, 2], dim4!(2));
+        }
+        let mut host = vec![0i32; 6];
+        a.host(&mut host);
+        assert_eq!(host, vec![1, 2, 3, 4, 51, 62]);
+
+        a.view_mut(0).unwrap().fill(0);
+        a.host(&mut host);
+        assert_eq!(host, vec![0, 2, 3, 0, 51, 62]);
+
+        let mut view = a.view_mut((.., 0)).unwrap();
+        let err = view.assign(&Array::new(&[1i32, 2], dim4!(2))).unwrap_err();
+        assert_eq!(err.code(), crate::AfError::ERR_SIZE);
+        drop(view);
+        a.host(&mut host);
+        assert_eq!(host, vec![0, 2, 3, 0, 51, 62]);
+    }
+}
@@ -1,5 +1,6 @@
 use super::core::{
-    af_array, af_window, AfError, Array, ColorMap, HasAfEnum, MarkerType, HANDLE_ERROR,
+    af_array, af_window, handle_wrapper_error, AfError, Array, ColorMap, HasAfEnum, MarkerType,
+    HANDLE_ERROR,
 };
 
 use libc::{c_char, c_double, c_float, c_int, c_uint};
@@ -256,7 +257,7 @@ impl Window {
                 let err_val = unsafe { af_set_title(self.handle, cstr.as_ptr()) };
                 HANDLE_ERROR(AfError::from(err_val));
             }
-            Err(_) => HANDLE_ERROR(AfError::ERR_INTERNAL),
+            Err(_) => handle_wrapper_error(AfError::ERR_INTERNAL, "title contains a nul byte"),
         }
     }
 
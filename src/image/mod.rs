@@ -1,9 +1,9 @@
 use super::core::{
-    af_array, dim_t, AfError, Array, BorderType, CannyThresholdType, ColorSpace, ConfidenceCCInput,
-    Connectivity, DeconvInput, DiffusionEq, EdgeComputable, FloatingPoint, FluxFn,
-    GrayRGBConvertible, HasAfEnum, ImageFilterType, ImageNativeType, InterpType, InverseDeconvAlgo,
-    IterativeDeconvAlgo, MomentType, MomentsComputable, RealFloating, RealNumber, YCCStd,
-    HANDLE_ERROR,
+    af_array, af_result, dim_t, AfError, AfResult, Array, BorderType, CannyThresholdType,
+    ColorSpace, ConfidenceCCInput, Connectivity, DeconvInput, DiffusionEq, EdgeComputable,
+    FloatingPoint, FluxFn, GrayRGBConvertible, HasAfEnum, ImageFilterType, ImageNativeType,
+    InterpType, InverseDeconvAlgo, IterativeDeconvAlgo, MomentType, MomentsComputable,
+    RealFloating, RealNumber, YCCStd, HANDLE_ERROR,
 };
 
 use libc::{c_char, c_double, c_float, c_int, c_uint};
@@ -933,7 +933,7 @@ where
 }
 
 macro_rules! filt_func_def {
-    ($doc_str: expr, $fn_name: ident, $ffi_name: ident) => {
+    ($doc_str: expr, $fn_name: ident, $try_name: ident, $ffi_name: ident) => {
         #[doc=$doc_str]
         ///
         ///# Parameters
@@ -963,18 +963,43 @@ macro_rules! filt_func_def {
             HANDLE_ERROR(AfError::from(err_val));
             temp.into()
         }
+
+        #[doc = concat!(
+            "Same as [", stringify!($fn_name), "](./fn.", stringify!($fn_name), ".html), ",
+            "but returns failures as [ArrayFireError](./struct.ArrayFireError.html) instead of ",
+            "invoking the error handler."
+        )]
+        pub fn $try_name<T>(input: &Array<T>, wlen: u64, wwid: u64, etype: BorderType) -> AfResult<Array<T>>
+        where
+            T: HasAfEnum + ImageFilterType,
+        {
+            let mut temp: af_array = std::ptr::null_mut();
+            let err_val = unsafe {
+                $ffi_name(
+                    &mut temp as *mut af_array,
+                    input.get(),
+                    wlen as dim_t,
+                    wwid as dim_t,
+                    etype as c_uint,
+                )
+            };
+            af_result(AfError::from(err_val))?;
+            Ok(temp.into())
+        }
     };
 }
 
-filt_func_def!("Median filter", medfilt, af_medfilt);
+filt_func_def!("Median filter", medfilt, try_medfilt, af_medfilt);
 filt_func_def!(
     "Box filter with minimum as box operation",
     minfilt,
+    try_minfilt,
     af_minfilt
 );
 filt_func_def!(
     "Box filter with maximum as box operation",
     maxfilt,
+    try_maxfilt,
     af_maxfilt
 );
 
@@ -1150,7 +1175,7 @@ where
 }
 
 macro_rules! grayrgb_func_def {
-    ($doc_str: expr, $fn_name: ident, $ffi_name: ident) => {
+    ($doc_str: expr, $fn_name: ident, $try_name: ident, $ffi_name: ident) => {
         #[doc=$doc_str]
         ///
         ///# Parameters
@@ -1171,14 +1196,39 @@ macro_rules! grayrgb_func_def {
             HANDLE_ERROR(AfError::from(err_val));
             temp.into()
         }
+
+        #[doc = concat!(
+            "Same as [", stringify!($fn_name), "](./fn.", stringify!($fn_name), ".html), ",
+            "but returns failures as [ArrayFireError](./struct.ArrayFireError.html) instead of ",
+            "invoking the error handler."
+        )]
+        pub fn $try_name<T>(input: &Array<T>, r: f32, g: f32, b: f32) -> AfResult<Array<T>>
+        where
+            T: HasAfEnum + GrayRGBConvertible,
+        {
+            let mut temp: af_array = std::ptr::null_mut();
+            let err_val = unsafe { $ffi_name(&mut temp as *mut af_array, input.get(), r, g, b) };
+            af_result(AfError::from(err_val))?;
+            Ok(temp.into())
+        }
     };
 }
 
-grayrgb_func_def!("Color(RGB) to Grayscale conversion", rgb2gray, af_rgb2gray);
-grayrgb_func_def!("Grayscale to Color(RGB) conversion", gray2rgb, af_gray2rgb);
+grayrgb_func_def!(
+    "Color(RGB) to Grayscale conversion",
+    rgb2gray,
+    try_rgb2gray,
+    af_rgb2gray
+);
+grayrgb_func_def!(
+    "Grayscale to Color(RGB) conversion",
+    gray2rgb,
+    try_gray2rgb,
+    af_gray2rgb
+);
 
 macro_rules! hsvrgb_func_def {
-    ($doc_str: expr, $fn_name: ident, $ffi_name: ident) => {
+    ($doc_str: expr, $fn_name: ident, $try_name: ident, $ffi_name: ident) => {
         #[doc=$doc_str]
         pub fn $fn_name<T>(input: &Array<T>) -> Array<T>
         where
@@ -1189,11 +1239,36 @@ macro_rules! hsvrgb_func_def {
             HANDLE_ERROR(AfError::from(err_val));
             temp.into()
         }
+
+        #[doc = concat!(
+            "Same as [", stringify!($fn_name), "](./fn.", stringify!($fn_name), ".html), ",
+            "but returns failures as [ArrayFireError](./struct.ArrayFireError.html) instead of ",
+            "invoking the error handler."
+        )]
+        pub fn $try_name<T>(input: &Array<T>) -> AfResult<Array<T>>
+        where
+            T: HasAfEnum + RealFloating,
+        {
+            let mut temp: af_array = std::ptr::null_mut();
+            let err_val = unsafe { $ffi_name(&mut temp as *mut af_array, input.get()) };
+            af_result(AfError::from(err_val))?;
+            Ok(temp.into())
+        }
     };
 }
 
-hsvrgb_func_def!("HSV to RGB color space conversion", hsv2rgb, af_hsv2rgb);
-hsvrgb_func_def!("RGB to HSV color space conversion", rgb2hsv, af_rgb2hsv);
+hsvrgb_func_def!(
+    "HSV to RGB color space conversion",
+    hsv2rgb,
+    try_hsv2rgb,
+    af_hsv2rgb
+);
+hsvrgb_func_def!(
+    "RGB to HSV color space conversion",
+    rgb2hsv,
+    try_rgb2hsv,
+    af_rgb2hsv
+);
 
 /// Generate an array with image windows as columns
 ///
@@ -42,6 +42,11 @@ pub use crate::algorithm::*;
 #[cfg(feature = "algorithm")]
 mod algorithm;
 
+#[cfg(feature = "autograd")]
+pub use crate::autograd::{Differentiable, Var};
+#[cfg(feature = "autograd")]
+mod autograd;
+
 #[cfg(feature = "blas")]
 pub use crate::blas::*;
 #[cfg(feature = "blas")]
@@ -82,6 +87,11 @@ pub use crate::statistics::*;
 #[cfg(feature = "statistics")]
 mod statistics;
 
+#[cfg(feature = "tensor")]
+pub use crate::tensor::*;
+#[cfg(feature = "tensor")]
+mod tensor;
+
 #[cfg(feature = "vision")]
 pub use crate::vision::*;
 #[cfg(feature = "vision")]
@@ -4,6 +4,9 @@ use super::core::{
 
 use libc::{c_int, c_uint};
 
+#[cfg(feature = "nn")]
+pub mod nn;
+
 extern "C" {
     fn af_convolve2_nn(
         out: *mut af_array,
@@ -0,0 +1,834 @@
+//! Neural network layers, loss functions and optimizers
+//!
+//! Layers operate on [Var](../struct.Var.html) objects, hence gradients of a loss computed from
+//! the output of a layer are obtained by calling [Var::backward](../struct.Var.html#method.backward).
+//! Trainable weights are held in [Parameter](./struct.Parameter.html) objects that are updated in
+//! place by an [Optimizer](./trait.Optimizer.html).
+//!
+//! Data layout follows the conventions of ArrayFire functions used underneath:
+//!
+//! - Dense data is laid out as `features x batch`
+//! - Image data is laid out as `height x width x channels x batch`, as expected by
+//!   [convolve2_nn](../fn.convolve2_nn.html)
+//!
+//! # Examples
+//!
+//! ```rust
+//! use arrayfire::nn::{Layer, Linear, Module, Optimizer, ReLU, Sequential, Sgd};
+//! use arrayfire::{dim4, randu, Var};
+//!
+//! let mut model = Sequential::<f32>::new()
+//!     .add(Linear::new(4, 8))
+//!     .add(ReLU)
+//!     .add(Linear::new(8, 1));
+//! let mut optimizer = Sgd::new(0.1, 0.9);
+//!
+//! let input = Var::constant(randu::<f32>(dim4!(4, 16)));
+//! let target = Var::constant(randu::<f32>(dim4!(1, 16)));
+//!
+//! let loss = arrayfire::nn::mse_loss(&model.forward(&input), &target);
+//! loss.backward();
+//! optimizer.step(model.parameters());
+//! ```
+
+use crate::algorithm::max;
+use crate::autograd::{Differentiable, Var};
+use crate::core::{
+    constant, ge, get_default_random_engine, handle_wrapper_error, random_uniform, randu, sqrt,
+    AfError, Array, Dim4, RandomEngine,
+};
+use crate::image::{unwrap, wrap};
+
+fn scalar<T: Differentiable>(value: f64) -> T {
+    T::fromf64(value)
+}
+
+/// Uniformly distributed values in the range `[-bound, bound)`
+fn uniform_init<T: Differentiable>(dims: Dim4, bound: f64) -> Array<T> {
+    let values = randu::<T>(dims) * scalar::<T>(2.0 * bound);
+    values - scalar::<T>(bound)
+}
+
+/// Trainable weights of a layer
+///
+/// The value of a parameter enters the computation graph as a leaf [Var](../struct.Var.html)
+/// the first time [var](./struct.Parameter.html#method.var) is called after the parameter was
+/// created or last updated. Gradients computed for that variable are returned by
+/// [grad](./struct.Parameter.html#method.grad).
+pub struct Parameter<T: Differentiable> {
+    value: Array<T>,
+    var: Option<Var<T>>,
+}
+
+impl<T: Differentiable> Parameter<T> {
+    /// Create a new parameter with the given initial value
+    pub fn new(value: Array<T>) -> Self {
+        Self { value, var: None }
+    }
+
+    /// Returns the current value
+    pub fn value(&self) -> &Array<T> {
+        &self.value
+    }
+
+    /// Returns the current value for in place modification
+    ///
+    /// Modifying the value clears the gradient.
+    pub fn value_mut(&mut self) -> &mut Array<T> {
+        self.var = None;
+        &mut self.value
+    }
+
+    /// Returns the leaf variable that represents this parameter in the computation graph
+    pub fn var(&mut self) -> Var<T> {
+        let value = &self.value;
+        self.var
+            .get_or_insert_with(|| Var::new(value.clone()))
+            .clone()
+    }
+
+    /// Returns the gradient computed for this parameter, if any
+    pub fn grad(&self) -> Option<Array<T>> {
+        self.var.as_ref().and_then(|var| var.grad())
+    }
+
+    /// Clear the gradient of this parameter
+    pub fn zero_grad(&mut self) {
+        self.var = None;
+    }
+}
+
+/// Common functionality of layers and models
+pub trait Module<T: Differentiable> {
+    /// Returns all trainable parameters
+    fn parameters(&mut self) -> Vec<&mut Parameter<T>> {
+        Vec::new()
+    }
+
+    /// Switch between training and evaluation behavior
+    ///
+    /// Layers such as [Dropout](./struct.Dropout.html) and [BatchNorm](./struct.BatchNorm.html)
+    /// behave differently during training. Modules are in training mode when created.
+    fn set_training(&mut self, _training: bool) {}
+
+    /// Clear gradients of all parameters
+    fn zero_grad(&mut self) {
+        for param in self.parameters() {
+            param.zero_grad();
+        }
+    }
+}
+
+/// A module that transforms an input variable
+pub trait Layer<T: Differentiable>: Module<T> {
+    /// Computes the output of the layer for `input`
+    fn forward(&mut self, input: &Var<T>) -> Var<T>;
+}
+
+/// Chain of layers applied one after another
+pub struct Sequential<T: Differentiable> {
+    layers: Vec<Box<dyn Layer<T>>>,
+}
+
+impl<T: Differentiable> Default for Sequential<T> {
+    fn default() -> Self {
+        Self { layers: Vec::new() }
+    }
+}
+
+impl<T: Differentiable> Sequential<T> {
+    /// Create an empty chain of layers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `layer` to the chain
+    #[allow(clippy::should_implement_trait)]
+    pub fn add<L: Layer<T> + 'static>(mut self, layer: L) -> Self {
+        self.layers.push(Box::new(layer));
+        self
+    }
+}
+
+impl<T: Differentiable> Module<T> for Sequential<T> {
+    fn parameters(&mut self) -> Vec<&mut Parameter<T>> {
+        self.layers
+            .iter_mut()
+            .flat_map(|layer| layer.parameters())
+            .collect()
+    }
+
+    fn set_training(&mut self, training: bool) {
+        for layer in self.layers.iter_mut() {
+            layer.set_training(training);
+        }
+    }
+}
+
+impl<T: Differentiable> Layer<T> for Sequential<T> {
+    fn forward(&mut self, input: &Var<T>) -> Var<T> {
+        self.layers
+            .iter_mut()
+            .fold(input.clone(), |x, layer| layer.forward(&x))
+    }
+}
+
+/// Fully connected layer
+///
+/// Computes `weight x input + bias` for input of dimensions `in_features x batch`. The
+/// weight has dimensions `out_features x in_features` and the bias `out_features x 1`.
+pub struct Linear<T: Differentiable> {
+    /// Weight matrix
+    pub weight: Parameter<T>,
+    /// Bias vector
+    pub bias: Parameter<T>,
+}
+
+impl<T: Differentiable> Linear<T> {
+    /// Create a fully connected layer with uniformly initialized weights
+    pub fn new(in_features: u64, out_features: u64) -> Self {
+        let bound = 1.0 / (in_features as f64).sqrt();
+        Self {
+            weight: Parameter::new(uniform_init(
+                Dim4::new(&[out_features, in_features, 1, 1]),
+                bound,
+            )),
+            bias: Parameter::new(uniform_init(Dim4::new(&[out_features, 1, 1, 1]), bound)),
+        }
+    }
+}
+
+impl<T: Differentiable> Module<T> for Linear<T> {
+    fn parameters(&mut self) -> Vec<&mut Parameter<T>> {
+        vec![&mut self.weight, &mut self.bias]
+    }
+}
+
+impl<T: Differentiable> Layer<T> for Linear<T> {
+    fn forward(&mut self, input: &Var<T>) -> Var<T> {
+        &self.weight.var().matmul(input) + &self.bias.var()
+    }
+}
+
+/// Two dimensional convolution layer backed by [convolve2_nn](../fn.convolve2_nn.html)
+///
+/// Input of dimensions `height x width x in_channels x batch` results in output of dimensions
+/// `out_height x out_width x out_channels x batch`. The filter has dimensions
+/// `kernel x kernel x in_channels x out_channels` and the bias `1 x 1 x out_channels x 1`.
+pub struct Conv2d<T: Differentiable> {
+    /// Convolution filters
+    pub filter: Parameter<T>,
+    /// Bias per output channel
+    pub bias: Parameter<T>,
+    strides: Dim4,
+    padding: Dim4,
+    dilation: Dim4,
+}
+
+impl<T: Differentiable> Conv2d<T> {
+    /// Create a convolution layer with square kernels of size `kernel`
+    pub fn new(
+        in_channels: u64,
+        out_channels: u64,
+        kernel: u64,
+        stride: u64,
+        padding: u64,
+    ) -> Self {
+        let fan_in = (in_channels * kernel * kernel) as f64;
+        let bound = 1.0 / fan_in.sqrt();
+        Self {
+            filter: Parameter::new(uniform_init(
+                Dim4::new(&[kernel, kernel, in_channels, out_channels]),
+                bound,
+            )),
+            bias: Parameter::new(uniform_init(Dim4::new(&[1, 1, out_channels, 1]), bound)),
+            strides: Dim4::new(&[stride, stride, 1, 1]),
+            padding: Dim4::new(&[padding, padding, 1, 1]),
+            dilation: Dim4::new(&[1, 1, 1, 1]),
+        }
+    }
+}
+
+impl<T: Differentiable> Module<T> for Conv2d<T> {
+    fn parameters(&mut self) -> Vec<&mut Parameter<T>> {
+        vec![&mut self.filter, &mut self.bias]
+    }
+}
+
+impl<T: Differentiable> Layer<T> for Conv2d<T> {
+    fn forward(&mut self, input: &Var<T>) -> Var<T> {
+        let conv = input.convolve2_nn(
+            &self.filter.var(),
+            self.strides,
+            self.padding,
+            self.dilation,
+        );
+        &conv + &self.bias.var()
+    }
+}
+
+/// Differentiable [unwrap](../fn.unwrap.html) of square, unpadded windows into columns
+fn unwrap_windows<T: Differentiable>(input: &Var<T>, window: u64, stride: u64) -> Var<T> {
+    let in_dims = input.dims();
+    let (w, s) = (window as i64, stride as i64);
+    let value = unwrap(input.value(), w, w, s, s, 0, 0, true);
+    Var::from_op(value, vec![input.clone()], move |g| {
+        vec![wrap(
+            g,
+            in_dims[0] as i64,
+            in_dims[1] as i64,
+            w,
+            w,
+            s,
+            s,
+            0,
+            0,
+            true,
+        )]
+    })
+}
+
+/// Report window or stride of zero, returns whether the parameters are valid
+fn check_pooling(window: u64, stride: u64) -> bool {
+    if window == 0 || stride == 0 {
+        handle_wrapper_error(
+            AfError::ERR_ARG,
+            &format!(
+                "pooling window {} and stride {} have to be positive",
+                window, stride
+            ),
+        );
+        return false;
+    }
+    true
+}
+
+/// Output dimensions of pooling, None after reporting invalid parameters
+fn pooled_dims(in_dims: Dim4, window: u64, stride: u64) -> Option<Dim4> {
+    if !check_pooling(window, stride) {
+        return None;
+    }
+    if in_dims[0] < window || in_dims[1] < window {
+        handle_wrapper_error(
+            AfError::ERR_SIZE,
+            &format!(
+                "pooling window {} is larger than input of dims {}",
+                window, in_dims
+            ),
+        );
+        return None;
+    }
+    Some(Dim4::new(&[
+        (in_dims[0] - window) / stride + 1,
+        (in_dims[1] - window) / stride + 1,
+        in_dims[2],
+        in_dims[3],
+    ]))
+}
+
+macro_rules! pool_layer {
+    ($doc_str: expr, $layer_name: ident, $reduce: ident) => {
+        #[doc=$doc_str]
+        ///
+        /// Windows are square and unpadded. Input of dimensions `height x width x channels x batch`
+        /// results in output of dimensions `out_height x out_width x channels x batch`.
+        pub struct $layer_name {
+            window: u64,
+            stride: u64,
+        }
+
+        impl $layer_name {
+            /// Create a pooling layer with windows of size `window x window`
+            ///
+            /// A `window` or `stride` of zero is reported as `ERR_ARG`. Input smaller than
+            /// the window is reported as `ERR_SIZE` by [forward](#method.forward).
+            pub fn new(window: u64, stride: u64) -> Self {
+                check_pooling(window, stride);
+                Self { window, stride }
+            }
+        }
+
+        impl<T: Differentiable> Module<T> for $layer_name {}
+
+        impl<T: Differentiable> Layer<T> for $layer_name {
+            fn forward(&mut self, input: &Var<T>) -> Var<T> {
+                let out_dims = match pooled_dims(input.dims(), self.window, self.stride) {
+                    Some(dims) => dims,
+                    None => return Var::constant(Array::new_empty(Dim4::new(&[0, 1, 1, 1]))),
+                };
+                unwrap_windows(input, self.window, self.stride)
+                    .$reduce(0)
+                    .moddims(out_dims)
+            }
+        }
+    };
+}
+
+pool_layer!("Two dimensional max pooling", MaxPool2d, max);
+pool_layer!("Two dimensional average pooling", AvgPool2d, mean);
+
+/// Collapse all but the last dimension
+///
+/// Converts image data of dimensions `height x width x channels x batch` to dense data of
+/// dimensions `features x batch`.
+pub struct Flatten;
+
+impl<T: Differentiable> Module<T> for Flatten {}
+
+impl<T: Differentiable> Layer<T> for Flatten {
+    fn forward(&mut self, input: &Var<T>) -> Var<T> {
+        let dims = input.dims();
+        input.moddims(Dim4::new(&[dims[0] * dims[1] * dims[2], dims[3], 1, 1]))
+    }
+}
+
+macro_rules! activation_layer {
+    ($doc_str: expr, $layer_name: ident, $method: ident) => {
+        #[doc=$doc_str]
+        pub struct $layer_name;
+
+        impl<T: Differentiable> Module<T> for $layer_name {}
+
+        impl<T: Differentiable> Layer<T> for $layer_name {
+            fn forward(&mut self, input: &Var<T>) -> Var<T> {
+                input.$method()
+            }
+        }
+    };
+}
+
+activation_layer!("Rectified linear unit activation", ReLU, relu);
+activation_layer!("Sigmoid activation", Sigmoid, sigmoid);
+activation_layer!("Hyperbolic tangent activation", Tanh, tanh);
+
+/// Batch normalization
+///
+/// Normalizes each feature, along dimension `channel_dim`, using mean and variance computed
+/// over all other dimensions. Use `channel_dim` 0 for dense data and 2 for image data.
+/// During evaluation, running estimates gathered while training are used instead.
+pub struct BatchNorm<T: Differentiable> {
+    /// Scale per feature
+    pub gamma: Parameter<T>,
+    /// Shift per feature
+    pub beta: Parameter<T>,
+    running_mean: Array<T>,
+    running_var: Array<T>,
+    channel_dim: usize,
+    momentum: f64,
+    epsilon: f64,
+    training: bool,
+}
+
+impl<T: Differentiable> BatchNorm<T> {
+    /// Create a batch normalization layer for `num_features` features along `channel_dim`
+    pub fn new(num_features: u64, channel_dim: usize) -> Self {
+        let mut dims = Dim4::new(&[1, 1, 1, 1]);
+        dims[channel_dim] = num_features;
+        Self {
+            gamma: Parameter::new(constant(scalar::<T>(1.0), dims)),
+            beta: Parameter::new(constant(scalar::<T>(0.0), dims)),
+            running_mean: constant(scalar::<T>(0.0), dims),
+            running_var: constant(scalar::<T>(1.0), dims),
+            channel_dim,
+            momentum: 0.1,
+            epsilon: 1e-5,
+            training: true,
+        }
+    }
+
+    /// Set the momentum used to update running estimates, default is 0.1
+    pub fn momentum(mut self, momentum: f64) -> Self {
+        self.momentum = momentum;
+        self
+    }
+
+    /// Set the value added to variance for numerical stability, default is 1e-5
+    pub fn epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    fn mean_over_batch(&self, input: &Var<T>) -> Var<T> {
+        (0..4)
+            .filter(|&dim| dim != self.channel_dim)
+            .fold(input.clone(), |acc, dim| acc.mean(dim as i32))
+    }
+}
+
+impl<T: Differentiable> Module<T> for BatchNorm<T> {
+    fn parameters(&mut self) -> Vec<&mut Parameter<T>> {
+        vec![&mut self.gamma, &mut self.beta]
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+impl<T: Differentiable> Layer<T> for BatchNorm<T> {
+    fn forward(&mut self, input: &Var<T>) -> Var<T> {
+        let (mean, var) = if self.training {
+            let mean = self.mean_over_batch(input);
+            let centered = input - &mean;
+            let var = self.mean_over_batch(&(&centered * &centered));
+
+            let m = scalar::<T>(self.momentum);
+            let keep = scalar::<T>(1.0 - self.momentum);
+            self.running_mean = &self.running_mean * keep + &(mean.value() * m);
+            self.running_var = &self.running_var * keep + &(var.value() * m);
+            (mean, var)
+        } else {
+            (
+                Var::constant(self.running_mean.clone()),
+                Var::constant(self.running_var.clone()),
+            )
+        };
+        let std_dev = (&var + scalar::<T>(self.epsilon)).sqrt();
+        let normalized = &(input - &mean) / &std_dev;
+        &(&normalized * &self.gamma.var()) + &self.beta.var()
+    }
+}
+
+/// Randomly zero out elements during training
+///
+/// Each element is dropped with probability `p` and the remaining ones are scaled by
+/// `1 / (1 - p)`. During evaluation, the input is passed through unchanged.
+pub struct Dropout {
+    p: f64,
+    engine: RandomEngine,
+    training: bool,
+}
+
+impl Dropout {
+    /// Create a dropout layer that uses the default random engine
+    pub fn new(p: f64) -> Self {
+        Self::with_engine(p, get_default_random_engine())
+    }
+
+    /// Create a dropout layer that draws random numbers from `engine`
+    ///
+    /// `p` has to lie in `[0, 1)`, other values are reported as `ERR_ARG` and, if the error
+    /// handler returns, the layer passes its input through unchanged.
+    pub fn with_engine(p: f64, engine: RandomEngine) -> Self {
+        let p = if (0.0..1.0).contains(&p) {
+            p
+        } else {
+            handle_wrapper_error(
+                AfError::ERR_ARG,
+                &format!("dropout probability {} is outside of [0, 1)", p),
+            );
+            0.0
+        };
+        Self {
+            p,
+            engine,
+            training: true,
+        }
+    }
+}
+
+impl<T: Differentiable> Module<T> for Dropout {
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+impl<T: Differentiable> Layer<T> for Dropout {
+    fn forward(&mut self, input: &Var<T>) -> Var<T> {
+        if !self.training || self.p <= 0.0 {
+            return input.clone();
+        }
+        let samples = random_uniform::<T>(input.dims(), &self.engine);
+        let keep = ge(&samples, &scalar::<T>(self.p), false).cast::<T>();
+        let mask = keep * scalar::<T>(1.0 / (1.0 - self.p));
+        input * &Var::constant(mask)
+    }
+}
+
+/// Compute logarithm of softmax along dimension `dim`
+pub fn log_softmax<T: Differentiable>(input: &Var<T>, dim: i32) -> Var<T> {
+    // Shifting by maximum value doesn't change the result, but avoids overflow
+    let shifted = input - &Var::constant(max(input.value(), dim));
+    &shifted - &shifted.exp().sum(dim).log()
+}
+
+/// Compute softmax along dimension `dim`
+pub fn softmax<T: Differentiable>(input: &Var<T>, dim: i32) -> Var<T> {
+    log_softmax(input, dim).exp()
+}
+
+/// Mean cross entropy loss of `logits` against one-hot encoded `targets`
+///
+/// Both inputs have dimensions `classes x batch`. The result is a single element variable.
+pub fn cross_entropy<T: Differentiable>(logits: &Var<T>, targets: &Var<T>) -> Var<T> {
+    let batch = logits.dims()[1] as f64;
+    let total = (targets * &log_softmax(logits, 0)).sum_all();
+    &total * scalar::<T>(-1.0 / batch)
+}
+
+/// Mean squared error between `prediction` and `target`
+///
+/// The result is a single element variable.
+pub fn mse_loss<T: Differentiable>(prediction: &Var<T>, target: &Var<T>) -> Var<T> {
+    let diff = prediction - target;
+    (&diff * &diff).mean_all()
+}
+
+/// Algorithm that updates parameters using their gradients
+pub trait Optimizer<T: Differentiable> {
+    /// Update `parameters` in place using their gradients
+    ///
+    /// Parameters without a gradient are left unchanged. The same parameters have to be
+    /// passed, in the same order, on every call since optimizers keep state per parameter.
+    /// Gradients of the parameters are cleared by this call.
+    fn step(&mut self, parameters: Vec<&mut Parameter<T>>);
+}
+
+/// Stochastic gradient descent with optional momentum
+pub struct Sgd<T: Differentiable> {
+    learning_rate: f64,
+    momentum: f64,
+    velocities: Vec<Option<Array<T>>>,
+}
+
+impl<T: Differentiable> Sgd<T> {
+    /// Create a new optimizer, pass `momentum` as zero for plain gradient descent
+    pub fn new(learning_rate: f64, momentum: f64) -> Self {
+        Self {
+            learning_rate,
+            momentum,
+            velocities: Vec::new(),
+        }
+    }
+}
+
+impl<T: Differentiable> Optimizer<T> for Sgd<T> {
+    fn step(&mut self, parameters: Vec<&mut Parameter<T>>) {
+        self.velocities.resize(parameters.len(), None);
+        for (param, velocity) in parameters.into_iter().zip(self.velocities.iter_mut()) {
+            let grad = match param.grad() {
+                Some(grad) => grad,
+                None => continue,
+            };
+            let update = match velocity.take() {
+                Some(prev) if self.momentum != 0.0 => prev * scalar::<T>(self.momentum) + &grad,
+                _ => grad,
+            };
+            *param.value_mut() -= &update * scalar::<T>(self.learning_rate);
+            *velocity = Some(update);
+        }
+    }
+}
+
+/// Adam optimizer
+pub struct Adam<T: Differentiable> {
+    learning_rate: f64,
+    beta1: f64,
+    beta2: f64,
+    epsilon: f64,
+    steps: i32,
+    moments: Vec<Option<(Array<T>, Array<T>)>>,
+}
+
+impl<T: Differentiable> Adam<T> {
+    /// Create a new optimizer with decay rates 0.9 and 0.999 for the moment estimates
+    pub fn new(learning_rate: f64) -> Self {
+        Self::with_params(learning_rate, 0.9, 0.999, 1e-8)
+    }
+
+    /// Create a new optimizer with given decay rates of moment estimates and `epsilon`
+    pub fn with_params(learning_rate: f64, beta1: f64, beta2: f64, epsilon: f64) -> Self {
+        Self {
+            learning_rate,
+            beta1,
+            beta2,
+            epsilon,
+            steps: 0,
+            moments: Vec::new(),
+        }
+    }
+}
+
+impl<T: Differentiable> Optimizer<T> for Adam<T> {
+    fn step(&mut self, parameters: Vec<&mut Parameter<T>>) {
+        self.steps += 1;
+        self.moments.resize(parameters.len(), None);
+
+        let (b1, b2) = (self.beta1, self.beta2);
+        let correction1 = 1.0 - b1.powi(self.steps);
+        let correction2 = 1.0 - b2.powi(self.steps);
+        let step_size = self.learning_rate * correction2.sqrt() / correction1;
+
+        for (param, moment) in parameters.into_iter().zip(self.moments.iter_mut()) {
+            let grad = match param.grad() {
+                Some(grad) => grad,
+                None => continue,
+            };
+            let (m, v) = match moment.take() {
+                Some((m, v)) => (
+                    m * scalar::<T>(b1) + &(&grad * scalar::<T>(1.0 - b1)),
+                    v * scalar::<T>(b2) + &(&grad * &grad * scalar::<T>(1.0 - b2)),
+                ),
+                None => (
+                    &grad * scalar::<T>(1.0 - b1),
+                    &grad * &grad * scalar::<T>(1.0 - b2),
+                ),
+            };
+            let denom = sqrt(&v) + scalar::<T>(self.epsilon * correction2.sqrt());
+            *param.value_mut() -= &m / &denom * scalar::<T>(step_size);
+            *moment = Some((m, v));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        mse_loss, Adam, AvgPool2d, BatchNorm, Conv2d, Dropout, Layer, Linear, MaxPool2d, Module,
+        Optimizer, Parameter, Sequential, Sgd, Tanh,
+    };
+    use crate::algorithm::{max_all, min_all, sum_all};
+    use crate::autograd::Var;
+    use crate::core::{constant, set_device, try_af, AfError, Array};
+    use crate::{dim4, randu};
+
+    #[test]
+    fn max_pool_forward_and_backward() {
+        set_device(0);
+        let values: Vec<f32> = (0..16).map(|v| v as f32).collect();
+        let input = Var::new(Array::new(&values, dim4!(4, 4)));
+
+        let out = MaxPool2d::new(2, 2).forward(&input);
+        assert_eq!(out.dims(), dim4!(2, 2));
+
+        let mut host = vec![0.0f32; 4];
+        out.value().host(&mut host);
+        assert_eq!(host, vec![5.0, 7.0, 13.0, 15.0]);
+
+        out.sum_all().backward();
+        assert_eq!(sum_all(&input.grad().unwrap()).0, 4.0);
+    }
+
+    #[test]
+    fn pooling_rejects_invalid_parameters() {
+        set_device(0);
+        let input = Var::new(randu!(3, 3));
+
+        let err = try_af(|| MaxPool2d::new(2, 0).forward(&input))
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), AfError::ERR_ARG);
+        let err = try_af(|| AvgPool2d::new(4, 1).forward(&input))
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), AfError::ERR_SIZE);
+    }
+
+    #[test]
+    fn conv2d_forward_and_backward() {
+        set_device(0);
+        let mut conv = Conv2d::<f32>::new(1, 2, 3, 1, 0);
+        let input = Var::new(randu!(5, 5, 1, 1));
+
+        let out = conv.forward(&input);
+        assert_eq!(out.dims(), dim4!(3, 3, 2, 1));
+
+        out.sum_all().backward();
+        assert_eq!(conv.filter.grad().unwrap().dims(), dim4!(3, 3, 1, 2));
+        let bias_grad = conv.bias.grad().unwrap();
+        assert_eq!(bias_grad.dims(), dim4!(1, 1, 2, 1));
+        assert_eq!(sum_all(&bias_grad).0, 18.0);
+        assert_eq!(input.grad().unwrap().dims(), dim4!(5, 5, 1, 1));
+    }
+
+    #[test]
+    fn batch_norm_normalizes_features() {
+        set_device(0);
+        let mut norm = BatchNorm::<f32>::new(3, 0);
+        let input = Var::new(randu!(3, 16) * 4.0f32 + 2.0f32);
+
+        let out = norm.forward(&input);
+        assert_eq!(out.dims(), dim4!(3, 16));
+        assert!(sum_all(out.value()).0.abs() < 1e-3);
+
+        out.sum_all().backward();
+        assert_eq!(sum_all(&norm.beta.grad().unwrap()).0, 48.0);
+        assert!(sum_all(&norm.gamma.grad().unwrap()).0.abs() < 1e-3);
+
+        norm.set_training(false);
+        assert_eq!(norm.forward(&input).dims(), dim4!(3, 16));
+    }
+
+    #[test]
+    fn dropout_scales_kept_elements() {
+        set_device(0);
+        let mut dropout = Dropout::new(0.5);
+        let input = Var::new(constant(1.0f32, dim4!(100)));
+
+        let out = Layer::<f32>::forward(&mut dropout, &input);
+        let kept = sum_all(out.value()).0;
+        assert!(kept > 0.0 && kept < 200.0);
+        assert_eq!(min_all(out.value()).0, 0.0);
+        assert_eq!(max_all(out.value()).0, 2.0);
+
+        out.sum_all().backward();
+        assert_eq!(sum_all(&input.grad().unwrap()).0, kept);
+
+        Module::<f32>::set_training(&mut dropout, false);
+        let out = dropout.forward(&input);
+        assert_eq!(sum_all(out.value()).0, 100.0);
+    }
+
+    #[test]
+    fn dropout_rejects_invalid_probability() {
+        set_device(0);
+        for p in [-0.5, 1.0, 1.5, f64::NAN] {
+            let err = try_af(|| Dropout::new(p)).err().unwrap();
+            assert_eq!(err.code(), AfError::ERR_ARG);
+        }
+        assert!(try_af(|| Dropout::new(0.0)).is_ok());
+    }
+
+    #[test]
+    fn adam_steps_by_learning_rate() {
+        set_device(0);
+        let mut param = Parameter::new(constant(1.0f32, dim4!(4)));
+        let mut optimizer = Adam::new(0.1);
+
+        let weight = param.var();
+        (&weight * &weight).sum_all().backward();
+        optimizer.step(vec![&mut param]);
+        assert!((sum_all(param.value()).0 - 3.6).abs() < 1e-4);
+        assert!(param.grad().is_none());
+
+        for _ in 0..20 {
+            let weight = param.var();
+            (&weight * &weight).sum_all().backward();
+            optimizer.step(vec![&mut param]);
+        }
+        assert!(sum_all(param.value()).0 < 3.6);
+    }
+
+    #[test]
+    fn sgd_reduces_regression_loss() {
+        set_device(0);
+        let mut model = Sequential::<f32>::new()
+            .add(Linear::new(3, 8))
+            .add(Tanh)
+            .add(Linear::new(8, 1));
+        let mut optimizer = Sgd::new(0.05, 0.9);
+
+        let input = Var::constant(randu!(3, 32));
+        let target = Var::constant(randu!(1, 32));
+
+        let mut losses = Vec::new();
+        for _ in 0..50 {
+            let loss = mse_loss(&model.forward(&input), &target);
+            loss.backward();
+            optimizer.step(model.parameters());
+            losses.push(sum_all(loss.value()).0);
+        }
+        assert!(losses.last().unwrap() < losses.first().unwrap());
+        assert!(model.parameters().iter().all(|p| p.grad().is_none()));
+    }
+}
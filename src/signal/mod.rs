@@ -1,6 +1,6 @@
 use super::core::{
-    af_array, dim_t, AfError, Array, ComplexFloating, ConvDomain, ConvMode, FloatingPoint,
-    HasAfEnum, InterpType, RealFloating, HANDLE_ERROR,
+    af_array, af_result, dim_t, AfError, AfResult, Array, ComplexFloating, ConvDomain, ConvMode,
+    FloatingPoint, HasAfEnum, InterpType, RealFloating, HANDLE_ERROR,
 };
 
 use libc::{c_double, c_float, c_int, c_uint, size_t};
@@ -689,7 +689,7 @@ where
 }
 
 macro_rules! conv_func_def {
-    ($doc_str: expr, $fn_name:ident, $ffi_name: ident) => {
+    ($doc_str: expr, $fn_name: ident, $try_name: ident, $ffi_name: ident) => {
         #[doc=$doc_str]
         ///
         ///# Parameters
@@ -727,12 +727,41 @@ macro_rules! conv_func_def {
             HANDLE_ERROR(AfError::from(err_val));
             temp.into()
         }
+
+        #[doc = concat!(
+            "Same as [", stringify!($fn_name), "](./fn.", stringify!($fn_name), ".html), ",
+            "but returns failures as [ArrayFireError](./struct.ArrayFireError.html) instead of ",
+            "invoking the error handler."
+        )]
+        pub fn $try_name<T, F>(
+            signal: &Array<T>,
+            filter: &Array<F>,
+            mode: ConvMode,
+            domain: ConvDomain,
+        ) -> AfResult<Array<T>>
+        where
+            T: HasAfEnum,
+            F: HasAfEnum,
+        {
+            let mut temp: af_array = std::ptr::null_mut();
+            let err_val = unsafe {
+                $ffi_name(
+                    &mut temp as *mut af_array,
+                    signal.get(),
+                    filter.get(),
+                    mode as c_uint,
+                    domain as c_uint,
+                )
+            };
+            af_result(AfError::from(err_val))?;
+            Ok(temp.into())
+        }
     };
 }
 
-conv_func_def!("1d convolution", convolve1, af_convolve1);
-conv_func_def!("2d convolution", convolve2, af_convolve2);
-conv_func_def!("3d convolution", convolve3, af_convolve3);
+conv_func_def!("1d convolution", convolve1, try_convolve1, af_convolve1);
+conv_func_def!("2d convolution", convolve2, try_convolve2, af_convolve2);
+conv_func_def!("3d convolution", convolve3, try_convolve3, af_convolve3);
 
 /// Separable convolution for 2d signals
 ///
@@ -771,7 +800,7 @@ where
 }
 
 macro_rules! fft_conv_func_def {
-    ($doc_str: expr, $fn_name:ident, $ffi_name: ident) => {
+    ($doc_str: expr, $fn_name: ident, $try_name: ident, $ffi_name: ident) => {
         #[doc=$doc_str]
         ///
         ///# Parameters
@@ -801,22 +830,48 @@ macro_rules! fft_conv_func_def {
             HANDLE_ERROR(AfError::from(err_val));
             temp.into()
         }
+
+        #[doc = concat!(
+            "Same as [", stringify!($fn_name), "](./fn.", stringify!($fn_name), ".html), ",
+            "but returns failures as [ArrayFireError](./struct.ArrayFireError.html) instead of ",
+            "invoking the error handler."
+        )]
+        pub fn $try_name<T, F>(signal: &Array<T>, filter: &Array<F>, mode: ConvMode) -> AfResult<Array<T>>
+        where
+            T: HasAfEnum,
+            F: HasAfEnum,
+        {
+            let mut temp: af_array = std::ptr::null_mut();
+            let err_val = unsafe {
+                $ffi_name(
+                    &mut temp as *mut af_array,
+                    signal.get(),
+                    filter.get(),
+                    mode as c_uint,
+                )
+            };
+            af_result(AfError::from(err_val))?;
+            Ok(temp.into())
+        }
     };
 }
 
 fft_conv_func_def!(
     "1d convolution using fast-fourier transform",
     fft_convolve1,
+    try_fft_convolve1,
     af_fft_convolve1
 );
 fft_conv_func_def!(
     "2d convolution using fast-fourier transform",
     fft_convolve2,
+    try_fft_convolve2,
     af_fft_convolve2
 );
 fft_conv_func_def!(
     "3d convolution using fast-fourier transform",
     fft_convolve3,
+    try_fft_convolve3,
     af_fft_convolve3
 );
 
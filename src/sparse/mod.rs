@@ -353,3 +353,168 @@ pub fn sparse_get_format<T: HasAfEnum>(input: &Array<T>) -> SparseFormat {
 
     SparseFormat::from(stype)
 }
+
+/// Serialization of sparse arrays for use with serde's `with` attribute
+///
+/// The default serde implementation of [Array](../struct.Array.html) handles dense arrays only.
+/// Sparse arrays are stored as their [SparseFormat](../enum.SparseFormat.html), dimensions and
+/// the values, row indices and column indices returned by
+/// [sparse_get_info](../fn.sparse_get_info.html). They are rebuilt in the same storage format
+/// on deserialization.
+///
+/// # Examples
+///
+/// ```rust
+/// use arrayfire::{sparse_from_dense, identity, Array, Dim4, SparseFormat};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Model {
+///     #[serde(with = "arrayfire::sparse_serde")]
+///     adjacency: Array<f32>,
+/// }
+///
+/// let dense = identity::<f32>(Dim4::new(&[4, 4, 1, 1]));
+/// let model = Model {
+///     adjacency: sparse_from_dense(&dense, SparseFormat::CSR),
+/// };
+/// let encoded = bincode::serialize(&model).unwrap();
+/// let decoded: Model = bincode::deserialize(&encoded).unwrap();
+/// assert!(decoded.adjacency.is_sparse());
+/// ```
+#[cfg(feature = "afserde")]
+pub mod sparse_serde {
+    // Reimport required from super scope
+    use super::{sparse_from_host, sparse_get_info};
+    use crate::core::{Array, FloatingPoint, HasAfEnum, SparseFormat};
+
+    use serde::de::{Deserializer, Error};
+    use serde::ser::Serializer;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct SparseOnHost<T> {
+        format: SparseFormat,
+        rows: u64,
+        cols: u64,
+        values: Vec<T>,
+        row_indices: Vec<i32>,
+        col_indices: Vec<i32>,
+    }
+
+    fn to_vec<T: HasAfEnum>(array: &Array<T>) -> Vec<T> {
+        let mut vec = vec![T::default(); array.elements()];
+        array.host(&mut vec);
+        vec
+    }
+
+    /// Serialize sparse Array `input`
+    pub fn serialize<T, S>(input: &Array<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: HasAfEnum + FloatingPoint + Serialize,
+        S: Serializer,
+    {
+        let dims = input.dims();
+        let (values, row_indices, col_indices, format) = sparse_get_info(input);
+        let sparse = SparseOnHost {
+            format,
+            rows: dims[0],
+            cols: dims[1],
+            values: to_vec(&values),
+            row_indices: to_vec(&row_indices),
+            col_indices: to_vec(&col_indices),
+        };
+        sparse.serialize(serializer)
+    }
+
+    /// Expected lengths of row and column index vectors, None if they can not be represented
+    fn index_lengths(format: SparseFormat, rows: u64, cols: u64, nnz: u64) -> Option<(u64, u64)> {
+        match format {
+            SparseFormat::CSR => Some((rows.checked_add(1)?, nnz)),
+            SparseFormat::CSC => Some((nnz, cols.checked_add(1)?)),
+            _ => Some((nnz, nnz)),
+        }
+    }
+
+    /// Deserialize sparse Array
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Array<T>, D::Error>
+    where
+        T: HasAfEnum + FloatingPoint + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let sparse = SparseOnHost::<T>::deserialize(deserializer)?;
+        if sparse.format == SparseFormat::DENSE {
+            return Err(Error::custom(
+                "sparse array can not have DENSE storage format",
+            ));
+        }
+        let nnz = sparse.values.len() as u64;
+        let (row_len, col_len) = index_lengths(sparse.format, sparse.rows, sparse.cols, nnz)
+            .ok_or_else(|| Error::custom("sparse array dimensions are too large"))?;
+        if sparse.row_indices.len() as u64 != row_len || sparse.col_indices.len() as u64 != col_len
+        {
+            return Err(Error::custom(format!(
+                "{:?} sparse array of {}x{} with {} values requires {} row and {} column \
+                 indices, found {} and {}",
+                sparse.format,
+                sparse.rows,
+                sparse.cols,
+                nnz,
+                row_len,
+                col_len,
+                sparse.row_indices.len(),
+                sparse.col_indices.len()
+            )));
+        }
+        Ok(sparse_from_host(
+            sparse.rows,
+            sparse.cols,
+            nnz,
+            &sparse.values,
+            &sparse.row_indices,
+            &sparse.col_indices,
+            sparse.format,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "afserde")]
+    mod serde_tests {
+        use crate::core::{Array, SparseFormat};
+        use crate::sparse::{sparse_from_host, sparse_get_info, sparse_to_dense};
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize)]
+        struct Wrapper {
+            #[serde(with = "crate::sparse::sparse_serde")]
+            array: Array<f32>,
+        }
+
+        #[test]
+        fn sparse_serde() {
+            crate::core::set_device(0);
+            let values = [1.0f32, 2.0, 3.0];
+            let rows = [0, 1, 2, 3];
+            let cols = [0, 2, 1];
+            let sparse = sparse_from_host(3, 3, 3, &values, &rows, &cols, SparseFormat::CSR);
+
+            let encoded = bincode::serialize(&Wrapper { array: sparse }).unwrap();
+            let decoded: Wrapper = bincode::deserialize(&encoded).unwrap();
+
+            assert!(decoded.array.is_sparse());
+            assert_eq!(sparse_get_info(&decoded.array).3, SparseFormat::CSR);
+            let mut dense = vec![0.0f32; 9];
+            sparse_to_dense(&decoded.array).host(&mut dense);
+            assert_eq!(dense, vec![1.0, 0.0, 0.0, 0.0, 0.0, 3.0, 0.0, 2.0, 0.0]);
+
+            let invalid = r#"{"array":{"format":"CSR","rows":3,"cols":3,"values":[1.0,2.0,3.0],
+                "row_indices":[0,1,2],"col_indices":[0,2,1]}}"#;
+            assert!(serde_json::from_str::<Wrapper>(invalid).is_err());
+            let invalid = r#"{"array":{"format":"COO","rows":3,"cols":3,"values":[1.0,2.0],
+                "row_indices":[0,1],"col_indices":[0,2,1]}}"#;
+            assert!(serde_json::from_str::<Wrapper>(invalid).is_err());
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use super::core::{
-    af_array, dim_t, AfError, Array, CovarianceComputable, HasAfEnum, MedianComputable,
-    RealFloating, RealNumber, TopkFn, VarianceBias, HANDLE_ERROR,
+    af_array, af_result, dim_t, AfError, AfResult, Array, CovarianceComputable, HasAfEnum,
+    MedianComputable, RealFloating, RealNumber, TopkFn, VarianceBias, HANDLE_ERROR,
 };
 
 use libc::{c_double, c_int, c_uint};
@@ -8,6 +8,7 @@ use libc::{c_double, c_int, c_uint};
 extern "C" {
     fn af_mean(out: *mut af_array, arr: af_array, dim: dim_t) -> c_int;
     fn af_median(out: *mut af_array, arr: af_array, dim: dim_t) -> c_int;
+    fn af_flat(out: *mut af_array, arr: af_array) -> c_int;
 
     fn af_mean_weighted(out: *mut af_array, arr: af_array, wts: af_array, dim: dim_t) -> c_int;
     fn af_var_weighted(out: *mut af_array, arr: af_array, wts: af_array, dim: dim_t) -> c_int;
@@ -384,6 +385,70 @@ where
     (real, imag)
 }
 
+fn flat<T: HasAfEnum>(input: &Array<T>) -> AfResult<Array<T>> {
+    let mut temp: af_array = std::ptr::null_mut();
+    let err_val = unsafe { af_flat(&mut temp as *mut af_array, input.get()) };
+    af_result(AfError::from(err_val))?;
+    Ok(temp.into())
+}
+
+/// Compute mean of all data as a value of the mean output type
+///
+/// Unlike [mean_all](./fn.mean_all.html), the mean of complex Arrays is returned as a
+/// complex number and the mean of `f32` Arrays as `f32`.
+///
+///# Parameters
+///
+/// - `input` is the input Array
+///
+///# Return Values
+///
+/// The mean of all elements. Fails if `input` is empty.
+pub fn mean_all_typed<T>(input: &Array<T>) -> AfResult<T::MeanOutType>
+where
+    T: HasAfEnum,
+    T::MeanOutType: HasAfEnum,
+{
+    let flat = flat(input)?;
+    let mut temp: af_array = std::ptr::null_mut();
+    let err_val = unsafe { af_mean(&mut temp as *mut af_array, flat.get(), 0) };
+    af_result(AfError::from(err_val))?;
+    let mean: Array<T::MeanOutType> = temp.into();
+    mean.scalar()
+}
+
+/// Compute variance of all data as a value of the mean output type
+///
+///# Parameters
+///
+/// - `input` is the input Array
+/// - `bias_kind` of type [VarianceBias][1] denotes the type of variane to be computed
+///
+///# Return Values
+///
+/// The variance of all elements. Fails if `input` is empty.
+///
+/// [1]: ./enum.VarianceBias.html
+pub fn var_all_typed<T>(input: &Array<T>, bias_kind: VarianceBias) -> AfResult<T::MeanOutType>
+where
+    T: HasAfEnum,
+    T::MeanOutType: HasAfEnum,
+{
+    let flat = flat(input)?;
+    let mut temp: af_array = std::ptr::null_mut();
+    let err_val = unsafe {
+        af_var_v2(
+            &mut temp as *mut af_array,
+            flat.get(),
+            bias_kind as c_uint,
+            0,
+        )
+    };
+    af_result(AfError::from(err_val))?;
+    let var: Array<T::MeanOutType> = temp.into();
+    var.scalar()
+}
+
 macro_rules! stat_wtd_all_func_def {
     ($doc_str: expr, $fn_name: ident, $ffi_fn: ident) => {
         #[doc=$doc_str]
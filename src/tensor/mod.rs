@@ -0,0 +1,417 @@
+use super::blas::{dot, matmul, transpose};
+use super::core::{
+    add, broadcast_dims, constant, div, mul, randu, sub, AfError, AfResult, Array, ArrayFireError,
+    ConstGenerator, Dim4, FloatingPoint, HasAfEnum, MatProp,
+};
+
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Sub};
+
+pub use nd::{NdArray, NdIndex};
+mod nd;
+
+/// Number of dimensions of a [Tensor](./struct.Tensor.html)
+pub trait Rank {
+    /// Number of dimensions that may have more than one element
+    const NDIMS: usize;
+}
+
+macro_rules! rank_def {
+    ($doc_str: expr, $rank: ident, $ndims: expr) => {
+        #[doc=$doc_str]
+        #[derive(Clone, Copy, Debug)]
+        pub struct $rank;
+
+        impl Rank for $rank {
+            const NDIMS: usize = $ndims;
+        }
+    };
+}
+
+rank_def!("Rank of vectors", Rank1, 1);
+rank_def!("Rank of matrices", Rank2, 2);
+rank_def!("Rank of three dimensional volumes", Rank3, 3);
+rank_def!("Rank of four dimensional data", Rank4, 4);
+
+fn shape_error(message: String) -> ArrayFireError {
+    ArrayFireError::with_message(AfError::ERR_SIZE, message)
+}
+
+/// Array with a fixed number of dimensions
+///
+/// The rank `D` restricts the dimensions that may hold more than one element, for example,
+/// `Tensor<T, Rank2>` is always a matrix. Operations check the shapes of their operands before
+/// calling into ArrayFire and return an [ArrayFireError](./struct.ArrayFireError.html) with
+/// code `ERR_SIZE` when they don't match. Use [Mat](./struct.Mat.html) to have shapes checked at
+/// compile time instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use arrayfire::{randu, AfError, Dim4, Rank2, Tensor};
+///
+/// let a = Tensor::<f32, Rank2>::new(randu(Dim4::new(&[3, 4, 1, 1]))).unwrap();
+/// let b = Tensor::<f32, Rank2>::new(randu(Dim4::new(&[3, 4, 1, 1]))).unwrap();
+///
+/// let err = a.matmul(&b).unwrap_err();
+/// assert_eq!(err.code(), AfError::ERR_SIZE);
+///
+/// let c = a.matmul(&b.transpose()).unwrap();
+/// assert_eq!(c.dims(), Dim4::new(&[3, 3, 1, 1]));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Tensor<T: HasAfEnum, D: Rank> {
+    array: Array<T>,
+    rank: PhantomData<D>,
+}
+
+impl<T: HasAfEnum, D: Rank> Tensor<T, D> {
+    /// Wrap `array`, failing if it has more dimensions than `D` allows
+    pub fn new(array: Array<T>) -> AfResult<Self> {
+        let dims = array.dims();
+        if dims.get()[D::NDIMS..].iter().any(|&d| d != 1) {
+            return Err(shape_error(format!(
+                "Array of dimensions {} has more than {} dimensions",
+                dims,
+                D::NDIMS
+            )));
+        }
+        Ok(Self {
+            array,
+            rank: PhantomData,
+        })
+    }
+
+    /// Returns the dimensions
+    pub fn dims(&self) -> Dim4 {
+        self.array.dims()
+    }
+
+    /// Returns the underlying Array
+    pub fn array(&self) -> &Array<T> {
+        &self.array
+    }
+
+    /// Unwraps the underlying Array
+    pub fn into_array(self) -> Array<T> {
+        self.array
+    }
+}
+
+macro_rules! tensor_arith_func {
+    ($doc_str: expr, $fn_name: ident, $op_name: ident) => {
+        #[doc=$doc_str]
+        ///
+        /// Dimensions of the operands have to be equal or one, dimensions of length one are
+        /// broadcast to match the other operand.
+        pub fn $fn_name(&self, rhs: &Self) -> AfResult<Self> {
+            broadcast_dims(self.dims(), rhs.dims())?;
+            Ok(Self {
+                array: $op_name(&self.array, &rhs.array, true),
+                rank: PhantomData,
+            })
+        }
+    };
+}
+
+impl<T: HasAfEnum, D: Rank> Tensor<T, D> {
+    tensor_arith_func!("Elementwise addition", add, add);
+    tensor_arith_func!("Elementwise subtraction", sub, sub);
+    tensor_arith_func!("Elementwise multiplication", mul, mul);
+    tensor_arith_func!("Elementwise division", div, div);
+}
+
+impl<T: HasAfEnum + FloatingPoint> Tensor<T, Rank2> {
+    /// Matrix multiplication, fails unless columns of `self` match rows of `rhs`
+    pub fn matmul(&self, rhs: &Self) -> AfResult<Self> {
+        let (ldims, rdims) = (self.dims(), rhs.dims());
+        if ldims[1] != rdims[0] {
+            return Err(shape_error(format!(
+                "can not multiply matrices of dimensions {} and {}",
+                ldims, rdims
+            )));
+        }
+        Ok(Self {
+            array: matmul(&self.array, &rhs.array, MatProp::NONE, MatProp::NONE),
+            rank: PhantomData,
+        })
+    }
+}
+
+impl<T: HasAfEnum> Tensor<T, Rank2> {
+    /// Matrix transpose
+    pub fn transpose(&self) -> Self {
+        Self {
+            array: transpose(&self.array, false),
+            rank: PhantomData,
+        }
+    }
+}
+
+impl<T: HasAfEnum + FloatingPoint> Tensor<T, Rank1> {
+    /// Dot product, fails unless both vectors are of the same length
+    pub fn dot(&self, rhs: &Self) -> AfResult<Array<T>> {
+        let (ldims, rdims) = (self.dims(), rhs.dims());
+        if ldims[0] != rdims[0] {
+            return Err(shape_error(format!(
+                "can not compute dot product of vectors of lengths {} and {}",
+                ldims[0], rdims[0]
+            )));
+        }
+        Ok(dot(&self.array, &rhs.array, MatProp::NONE, MatProp::NONE))
+    }
+}
+
+/// Matrix with number of rows and columns fixed at compile time
+///
+/// Operations whose result shape is determined by the operand shapes, such as
+/// [matmul](./struct.Mat.html#method.matmul), only accept operands of matching shapes, hence shape
+/// errors are reported by the compiler. Shapes are checked once, when an
+/// [Array](./struct.Array.html) is wrapped using [from_array](./struct.Mat.html#method.from_array).
+///
+/// # Examples
+///
+/// ```rust
+/// use arrayfire::Mat;
+///
+/// let a = Mat::<f32, 2, 3>::new(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+/// let b = Mat::<f32, 3, 1>::new(&[1.0, 1.0, 1.0]).unwrap();
+/// let bias = Mat::<f32, 2, 1>::new(&[0.5, -0.5]).unwrap();
+///
+/// // 2x3 times 3x1 results in 2x1, any other shape for `b` fails to compile
+/// let c: Mat<f32, 2, 1> = a.matmul(&b);
+/// let d = &c + &bias;
+///
+/// // Adds the row to every row of `a`
+/// let row = Mat::<f32, 1, 3>::new(&[1.0, 2.0, 3.0]).unwrap();
+/// let e = a.broadcast_add(&row);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Mat<T: HasAfEnum, const R: u64, const C: u64> {
+    array: Array<T>,
+}
+
+/// Compile time check that a `R2 x C2` matrix can be broadcast to `R x C`
+struct BroadcastCheck<const R: u64, const C: u64, const R2: u64, const C2: u64>;
+
+impl<const R: u64, const C: u64, const R2: u64, const C2: u64> BroadcastCheck<R, C, R2, C2> {
+    const OK: () = assert!(
+        (R2 == R || R2 == 1) && (C2 == C || C2 == 1),
+        "matrix shape can not be broadcast"
+    );
+}
+
+impl<T: HasAfEnum, const R: u64, const C: u64> Mat<T, R, C> {
+    fn dims() -> Dim4 {
+        Dim4::new(&[R, C, 1, 1])
+    }
+
+    /// Create a matrix from `values` stored in column major order
+    ///
+    /// Fails unless `values` holds exactly `R * C` elements.
+    pub fn new(values: &[T]) -> AfResult<Self> {
+        if values.len() as u64 != R * C {
+            return Err(shape_error(format!(
+                "{} values can not fill a {}x{} matrix",
+                values.len(),
+                R,
+                C
+            )));
+        }
+        Ok(Self {
+            array: Array::new(values, Self::dims()),
+        })
+    }
+
+    /// Wrap `array`, failing unless it is a `R x C` matrix
+    pub fn from_array(array: Array<T>) -> AfResult<Self> {
+        let dims = array.dims();
+        if dims != Self::dims() {
+            return Err(shape_error(format!(
+                "Array of dimensions {} is not a {}x{} matrix",
+                dims, R, C
+            )));
+        }
+        Ok(Self { array })
+    }
+
+    /// Returns the underlying Array
+    pub fn array(&self) -> &Array<T> {
+        &self.array
+    }
+
+    /// Unwraps the underlying Array
+    pub fn into_array(self) -> Array<T> {
+        self.array
+    }
+
+    /// Converts into a matrix whose shape is checked at run time
+    pub fn into_tensor(self) -> Tensor<T, Rank2> {
+        Tensor {
+            array: self.array,
+            rank: PhantomData,
+        }
+    }
+
+    /// Matrix transpose
+    pub fn transpose(&self) -> Mat<T, C, R> {
+        Mat {
+            array: transpose(&self.array, false),
+        }
+    }
+}
+
+impl<T, const R: u64, const C: u64> Mat<T, R, C>
+where
+    T: HasAfEnum + ConstGenerator<OutType = T>,
+{
+    /// Create a matrix with all elements set to `value`
+    pub fn constant(value: T) -> Self {
+        Self {
+            array: constant(value, Self::dims()),
+        }
+    }
+}
+
+impl<T: HasAfEnum, const R: u64, const C: u64> Mat<T, R, C> {
+    /// Create a matrix of uniformly distributed random values
+    pub fn randu() -> Self {
+        Self {
+            array: randu::<T>(Self::dims()),
+        }
+    }
+}
+
+impl<T: HasAfEnum + FloatingPoint, const R: u64, const C: u64> Mat<T, R, C> {
+    /// Matrix multiplication of `R x C` and `C x K` matrices
+    pub fn matmul<const K: u64>(&self, rhs: &Mat<T, C, K>) -> Mat<T, R, K> {
+        Mat {
+            array: matmul(&self.array, &rhs.array, MatProp::NONE, MatProp::NONE),
+        }
+    }
+}
+
+impl<T: HasAfEnum + FloatingPoint, const N: u64> Mat<T, N, 1> {
+    /// Dot product of two column vectors
+    pub fn dot(&self, rhs: &Self) -> Mat<T, 1, 1> {
+        Mat {
+            array: dot(&self.array, &rhs.array, MatProp::NONE, MatProp::NONE),
+        }
+    }
+}
+
+macro_rules! mat_arith_def {
+    ($doc_str: expr, $op_trait: ident, $op_fn: ident, $broadcast_fn: ident, $af_fn: ident) => {
+        impl<T: HasAfEnum, const R: u64, const C: u64> Mat<T, R, C> {
+            #[doc=$doc_str]
+            ///
+            /// Each dimension of `rhs` has to either match that of `self` or be one, in which
+            /// case `rhs` is repeated along that dimension. Other shapes fail to compile.
+            pub fn $broadcast_fn<const R2: u64, const C2: u64>(
+                &self,
+                rhs: &Mat<T, R2, C2>,
+            ) -> Mat<T, R, C> {
+                #[allow(clippy::let_unit_value)]
+                let () = BroadcastCheck::<R, C, R2, C2>::OK;
+                Mat {
+                    array: $af_fn(&self.array, &rhs.array, true),
+                }
+            }
+        }
+
+        impl<'a, T: HasAfEnum, const R: u64, const C: u64> $op_trait<&'a Mat<T, R, C>>
+            for &'a Mat<T, R, C>
+        {
+            type Output = Mat<T, R, C>;
+
+            fn $op_fn(self, rhs: &'a Mat<T, R, C>) -> Self::Output {
+                Mat {
+                    array: $af_fn(&self.array, &rhs.array, false),
+                }
+            }
+        }
+
+        impl<T: HasAfEnum, const R: u64, const C: u64> $op_trait for Mat<T, R, C> {
+            type Output = Mat<T, R, C>;
+
+            fn $op_fn(self, rhs: Self) -> Self::Output {
+                (&self).$op_fn(&rhs)
+            }
+        }
+    };
+}
+
+mat_arith_def!(
+    "Elementwise addition with broadcasting",
+    Add,
+    add,
+    broadcast_add,
+    add
+);
+mat_arith_def!(
+    "Elementwise subtraction with broadcasting",
+    Sub,
+    sub,
+    broadcast_sub,
+    sub
+);
+mat_arith_def!(
+    "Elementwise multiplication with broadcasting",
+    Mul,
+    mul,
+    broadcast_mul,
+    mul
+);
+mat_arith_def!(
+    "Elementwise division with broadcasting",
+    Div,
+    div,
+    broadcast_div,
+    div
+);
+
+#[cfg(test)]
+mod tests {
+    use super::{Mat, Rank1, Rank2, Tensor};
+    use crate::core::{set_device, AfError, Array};
+    use crate::dim4;
+
+    fn to_vec(array: &Array<f32>) -> Vec<f32> {
+        let mut data = vec![0.0f32; array.elements()];
+        array.host(&mut data);
+        data
+    }
+
+    #[test]
+    fn mat_matmul_and_broadcast() {
+        set_device(0);
+        let a = Mat::<f32, 2, 2>::new(&[1.0, 2.0, 3.0, 4.0]).unwrap();
+        let b = Mat::<f32, 2, 1>::new(&[1.0, 1.0]).unwrap();
+        let c = a.matmul(&b);
+        assert_eq!(to_vec(c.array()), vec![4.0, 6.0]);
+
+        let row = Mat::<f32, 1, 2>::new(&[10.0, 20.0]).unwrap();
+        let d = a.broadcast_add(&row);
+        assert_eq!(to_vec(d.array()), vec![11.0, 12.0, 23.0, 24.0]);
+        assert_eq!(to_vec(b.dot(&b).array()), vec![2.0]);
+
+        assert!(Mat::<f32, 3, 3>::new(&[1.0; 4]).is_err());
+        assert!(Mat::<f32, 3, 1>::from_array(c.into_array()).is_err());
+    }
+
+    #[test]
+    fn tensor_shape_checks() {
+        set_device(0);
+        let a = Tensor::<f32, Rank2>::new(Array::new(&[1.0; 6], dim4!(2, 3))).unwrap();
+        let b = Tensor::<f32, Rank2>::new(Array::new(&[1.0; 3], dim4!(1, 3))).unwrap();
+        assert_eq!(a.add(&b).unwrap().dims(), dim4!(2, 3));
+        assert_eq!(a.matmul(&b).unwrap_err().code(), AfError::ERR_SIZE);
+        assert_eq!(a.matmul(&b.transpose()).unwrap().dims(), dim4!(2, 1));
+
+        let v = Tensor::<f32, Rank1>::new(Array::new(&[1.0; 3], dim4!(3))).unwrap();
+        let w = Tensor::<f32, Rank1>::new(Array::new(&[1.0; 2], dim4!(2))).unwrap();
+        assert!(v.dot(&w).is_err());
+        assert!(v.add(&w).is_err());
+        assert!(Tensor::<f32, Rank1>::new(Array::new(&[1.0; 6], dim4!(2, 3))).is_err());
+    }
+}
@@ -0,0 +1,488 @@
+use super::super::algorithm::{max, min, product, sum};
+use super::super::core::{
+    index, moddims, reorder_v2, AfError, AfResult, Array, ArrayFireError, Dim4, HasAfEnum, Seq,
+};
+use super::super::statistics::mean;
+
+use std::convert::TryFrom;
+use std::ops::{Range, RangeFrom, RangeFull, RangeInclusive};
+
+fn shape_error(message: String) -> ArrayFireError {
+    ArrayFireError::with_message(AfError::ERR_SIZE, message)
+}
+
+fn fold_error(message: String) -> ArrayFireError {
+    ArrayFireError::with_message(AfError::ERR_NOT_SUPPORTED, message)
+}
+
+/// Folds an N dimensional shape into four dimensions
+///
+/// Shapes with less than four dimensions are padded with ones, dimensions from the
+/// fourth one onwards are folded into the fourth dimension.
+fn fold(shape: &[u64]) -> Dim4 {
+    let mut dims = [1u64; 4];
+    for (i, &len) in shape.iter().enumerate() {
+        dims[i.min(3)] *= len;
+    }
+    Dim4::new(&dims)
+}
+
+/// Folds consecutive groups of dimensions, given by their lengths, into at most four dimensions
+fn fold_groups(groups: &[u64]) -> Dim4 {
+    let mut dims = [1u64; 4];
+    dims[..groups.len()].copy_from_slice(groups);
+    Dim4::new(&dims)
+}
+
+/// Index along one dimension of an [NdArray](./struct.NdArray.html)
+///
+/// Usually created from Rust ranges and integers using `into()`. Ranges exclude their end,
+/// integers select a single element while keeping the dimension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NdIndex {
+    /// All elements along the dimension
+    All,
+    /// Elements `start..end`
+    Range(u64, u64),
+    /// Elements from `start` to the end of the dimension
+    From(u64),
+    /// A single element
+    At(u64),
+}
+
+impl From<RangeFull> for NdIndex {
+    fn from(_: RangeFull) -> Self {
+        NdIndex::All
+    }
+}
+
+/// Converts integers, negative values are out of bounds for any dimension
+fn to_index<T>(value: T) -> u64
+where
+    u64: TryFrom<T>,
+{
+    u64::try_from(value).unwrap_or(u64::MAX)
+}
+
+macro_rules! nd_index_from_def {
+    ($($idx_type: ty),+) => {
+        $(
+            impl From<$idx_type> for NdIndex {
+                fn from(value: $idx_type) -> Self {
+                    NdIndex::At(to_index(value))
+                }
+            }
+
+            impl From<Range<$idx_type>> for NdIndex {
+                fn from(range: Range<$idx_type>) -> Self {
+                    NdIndex::Range(to_index(range.start), to_index(range.end))
+                }
+            }
+
+            impl From<RangeInclusive<$idx_type>> for NdIndex {
+                fn from(range: RangeInclusive<$idx_type>) -> Self {
+                    let (start, end) = range.into_inner();
+                    NdIndex::Range(to_index(start), to_index(end).saturating_add(1))
+                }
+            }
+
+            impl From<RangeFrom<$idx_type>> for NdIndex {
+                fn from(range: RangeFrom<$idx_type>) -> Self {
+                    NdIndex::From(to_index(range.start))
+                }
+            }
+        )+
+    };
+}
+
+nd_index_from_def!(i32, i64, u32, u64, usize);
+
+impl NdIndex {
+    /// Returns the first selected position and the number of selected elements
+    fn resolve(&self, axis: usize, len: u64) -> AfResult<(u64, u64)> {
+        let (start, end) = match *self {
+            NdIndex::All => (0, len),
+            NdIndex::Range(start, end) => (start, end),
+            NdIndex::From(start) => (start, len),
+            NdIndex::At(index) => (index, index.saturating_add(1)),
+        };
+        if start > end || end > len {
+            return Err(shape_error(format!(
+                "index {:?} is out of bounds for axis {} of length {}",
+                self, axis, len
+            )));
+        }
+        Ok((start, end - start))
+    }
+}
+
+/// Array with an arbitrary number of dimensions
+///
+/// The elements are stored in an [Array](./struct.Array.html) in column major order, i.e. the
+/// first dimension varies fastest, with the dimensions from the fourth one onwards folded into
+/// the fourth dimension of the Array. Operations fold the N dimensional shape into at most
+/// four dimensions suitable for ArrayFire. Operations that can't be expressed that way, such
+/// as indexing five dimensions with partial ranges, fail with `ERR_NOT_SUPPORTED`.
+///
+/// Elementwise operations can be applied to the underlying Array directly using
+/// [map](./struct.NdArray.html#method.map), since they don't depend on the shape.
+///
+/// # Examples
+///
+/// ```rust
+/// use arrayfire::{randu, NdArray, NdIndex};
+///
+/// // width x height x frames x channels x batch
+/// let data = randu::<f32>(arrayfire::dim4!(64, 48, 16, 6));
+/// let video = NdArray::from_array(data, &[64, 48, 16, 3, 2]).unwrap();
+/// let frame = video.index(&[NdIndex::All, NdIndex::All, 4.into(), 0.into()]).unwrap();
+/// assert_eq!(frame.shape(), &[64, 48, 1, 1, 2]);
+///
+/// let per_channel = video.mean(3).unwrap();
+/// assert_eq!(per_channel.shape(), &[64, 48, 16, 1, 2]);
+///
+/// let channels_first = video.permute(&[3, 0, 1, 2, 4]).unwrap();
+/// assert_eq!(channels_first.shape(), &[3, 64, 48, 16, 2]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct NdArray<T: HasAfEnum> {
+    array: Array<T>,
+    shape: Vec<u64>,
+}
+
+impl<T: HasAfEnum> NdArray<T> {
+    /// Create an NdArray of shape `shape` from host values in column major order
+    pub fn new(values: &[T], shape: &[u64]) -> AfResult<Self> {
+        let elements: u64 = shape.iter().product();
+        if elements != values.len() as u64 {
+            return Err(shape_error(format!(
+                "shape {:?} requires {} elements, found {}",
+                shape,
+                elements,
+                values.len()
+            )));
+        }
+        Ok(Self {
+            array: Array::new(values, fold(shape)),
+            shape: shape.to_vec(),
+        })
+    }
+
+    /// Interpret the elements of `array`, in column major order, as an NdArray of shape `shape`
+    pub fn from_array(array: Array<T>, shape: &[u64]) -> AfResult<Self> {
+        let elements: u64 = shape.iter().product();
+        if elements != array.elements() as u64 {
+            return Err(shape_error(format!(
+                "shape {:?} requires {} elements, Array has {}",
+                shape,
+                elements,
+                array.elements()
+            )));
+        }
+        let dims = fold(shape);
+        let array = if array.dims() == dims {
+            array
+        } else {
+            moddims(&array, dims)
+        };
+        Ok(Self {
+            array,
+            shape: shape.to_vec(),
+        })
+    }
+
+    /// Returns the shape
+    pub fn shape(&self) -> &[u64] {
+        &self.shape
+    }
+
+    /// Returns the number of dimensions
+    pub fn ndims(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// Returns the number of elements
+    pub fn elements(&self) -> usize {
+        self.array.elements()
+    }
+
+    /// Returns the underlying Array with the folded dimensions
+    pub fn array(&self) -> &Array<T> {
+        &self.array
+    }
+
+    /// Unwraps the underlying Array with the folded dimensions
+    pub fn into_array(self) -> Array<T> {
+        self.array
+    }
+
+    /// Copy the elements, in column major order, to host
+    pub fn host(&self, data: &mut [T]) {
+        self.array.host(data)
+    }
+
+    /// Apply an elementwise operation to the underlying Array
+    ///
+    /// Fails with `ERR_SIZE` if `f` changes the number of elements.
+    pub fn map<O, F>(&self, f: F) -> AfResult<NdArray<O>>
+    where
+        O: HasAfEnum,
+        F: FnOnce(&Array<T>) -> Array<O>,
+    {
+        NdArray::from_array(f(&self.array), &self.shape)
+    }
+
+    /// Change the shape while keeping the elements in column major order
+    pub fn reshape(&self, shape: &[u64]) -> AfResult<Self> {
+        Self::from_array(self.array.clone(), shape)
+    }
+
+    fn check_axis(&self, axis: usize) -> AfResult<()> {
+        if axis < self.ndims() {
+            Ok(())
+        } else {
+            Err(ArrayFireError::with_message(
+                AfError::ERR_ARG,
+                format!("axis {} is invalid for {} dimensions", axis, self.ndims()),
+            ))
+        }
+    }
+
+    /// Reduces along `axis` by folding the dimensions before and after it
+    fn reduce<O, F>(&self, axis: usize, f: F) -> AfResult<NdArray<O>>
+    where
+        O: HasAfEnum,
+        F: FnOnce(&Array<T>, i32) -> Array<O>,
+    {
+        self.check_axis(axis)?;
+        let before: u64 = self.shape[..axis].iter().product();
+        let after: u64 = self.shape[axis + 1..].iter().product();
+        let folded = moddims(&self.array, fold_groups(&[before, self.shape[axis], after]));
+        let mut shape = self.shape.clone();
+        shape[axis] = 1;
+        NdArray::from_array(f(&folded, 1), &shape)
+    }
+
+    /// Sum along `axis`, keeping it with length one
+    pub fn sum(&self, axis: usize) -> AfResult<NdArray<T::AggregateOutType>> {
+        self.reduce(axis, |array, dim| sum(array, dim))
+    }
+
+    /// Multiply along `axis`, keeping it with length one
+    pub fn product(&self, axis: usize) -> AfResult<NdArray<T::ProductOutType>> {
+        self.reduce(axis, |array, dim| product(array, dim))
+    }
+
+    /// Minimum along `axis`, keeping it with length one
+    pub fn min(&self, axis: usize) -> AfResult<NdArray<T::InType>> {
+        self.reduce(axis, |array, dim| min(array, dim))
+    }
+
+    /// Maximum along `axis`, keeping it with length one
+    pub fn max(&self, axis: usize) -> AfResult<NdArray<T::InType>> {
+        self.reduce(axis, |array, dim| max(array, dim))
+    }
+
+    /// Mean along `axis`, keeping it with length one
+    pub fn mean(&self, axis: usize) -> AfResult<NdArray<T::MeanOutType>>
+    where
+        T::MeanOutType: HasAfEnum,
+    {
+        self.reduce(axis, |array, dim| mean(array, dim as i64))
+    }
+
+    /// Reorder the dimensions, dimension `i` of the result is dimension `axes[i]` of `self`
+    ///
+    /// Dimensions that stay next to each other in the same order are moved together. Fails
+    /// with `ERR_NOT_SUPPORTED` if more than four such groups of dimensions have to be moved.
+    pub fn permute(&self, axes: &[usize]) -> AfResult<Self> {
+        let ndims = self.ndims();
+        let mut seen = vec![false; ndims];
+        if axes.len() != ndims
+            || axes
+                .iter()
+                .any(|&a| a >= ndims || std::mem::replace(&mut seen[a], true))
+        {
+            return Err(ArrayFireError::with_message(
+                AfError::ERR_ARG,
+                format!("{:?} is not a permutation of {} axes", axes, ndims),
+            ));
+        }
+
+        // Runs of axes that stay adjacent, as (first input axis, number of axes), in output order
+        let mut groups: Vec<(usize, usize)> = Vec::new();
+        for &axis in axes {
+            match groups.last_mut() {
+                Some((first, count)) if *first + *count == axis => *count += 1,
+                _ => groups.push((axis, 1)),
+            }
+        }
+        if groups.len() > 4 {
+            return Err(fold_error(format!(
+                "permutation {:?} moves {} groups of dimensions, at most 4 can be folded",
+                axes,
+                groups.len()
+            )));
+        }
+
+        let shape: Vec<u64> = axes.iter().map(|&a| self.shape[a]).collect();
+        if groups.len() == 1 {
+            return Self::from_array(self.array.clone(), &shape);
+        }
+
+        let mut by_input: Vec<usize> = (0..groups.len()).collect();
+        by_input.sort_by_key(|&g| groups[g].0);
+        let lengths: Vec<u64> = by_input
+            .iter()
+            .map(|&g| {
+                let (first, count) = groups[g];
+                self.shape[first..first + count].iter().product()
+            })
+            .collect();
+        let mut order = [0u64, 1, 2, 3];
+        for (position, group) in groups.iter().enumerate() {
+            order[position] = by_input.iter().position(|&g| groups[g] == *group).unwrap() as u64;
+        }
+
+        let folded = moddims(&self.array, fold_groups(&lengths));
+        let reordered = reorder_v2(&folded, order[0], order[1], Some(vec![order[2], order[3]]));
+        Self::from_array(reordered, &shape)
+    }
+
+    /// Select elements using one [NdIndex](./enum.NdIndex.html) per dimension
+    ///
+    /// Dimensions beyond `indices` are kept whole. A dimension that is indexed partially is
+    /// folded together with the whole dimensions directly before it. Fails with `ERR_SIZE` if
+    /// an index is out of bounds and with `ERR_NOT_SUPPORTED` if more than four such folded
+    /// dimensions result.
+    pub fn index(&self, indices: &[NdIndex]) -> AfResult<Self> {
+        if indices.len() > self.ndims() {
+            return Err(ArrayFireError::with_message(
+                AfError::ERR_ARG,
+                format!(
+                    "{} indices given for {} dimensions",
+                    indices.len(),
+                    self.ndims()
+                ),
+            ));
+        }
+
+        let mut shape = Vec::with_capacity(self.ndims());
+        // Folded dimensions as (length, first selected position, number of selected elements)
+        let mut groups: Vec<(u64, u64, u64)> = Vec::new();
+        let mut whole: u64 = 1;
+        for (axis, &len) in self.shape.iter().enumerate() {
+            let (start, count) = indices
+                .get(axis)
+                .unwrap_or(&NdIndex::All)
+                .resolve(axis, len)?;
+            shape.push(count);
+            if start == 0 && count == len {
+                whole *= len;
+            } else {
+                groups.push((whole * len, whole * start, whole * count));
+                whole = 1;
+            }
+        }
+        if whole > 1 || groups.is_empty() {
+            groups.push((whole, 0, whole));
+        }
+        if groups.len() > 4 {
+            return Err(fold_error(format!(
+                "indices {:?} select from {} folded dimensions, at most 4 are supported",
+                indices,
+                groups.len()
+            )));
+        }
+
+        if shape.contains(&0) {
+            return Ok(Self {
+                array: Array::new_empty(fold(&shape)),
+                shape,
+            });
+        }
+        let lengths: Vec<u64> = groups.iter().map(|g| g.0).collect();
+        let seqs: Vec<Seq<f64>> = groups
+            .iter()
+            .map(|&(_, start, count)| Seq::new(start as f64, (start + count - 1) as f64, 1.0))
+            .collect();
+        let folded = moddims(&self.array, fold_groups(&lengths));
+        Self::from_array(index(&folded, &seqs), &shape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NdArray, NdIndex};
+    use crate::core::{set_device, AfError};
+
+    fn to_vec(array: &NdArray<f32>) -> Vec<f32> {
+        let mut data = vec![0.0f32; array.elements()];
+        array.host(&mut data);
+        data
+    }
+
+    /// Column major position of `index` in an array of shape `shape`
+    fn position(index: &[u64], shape: &[u64]) -> f32 {
+        let mut stride = 1;
+        let mut pos = 0;
+        for (i, len) in index.iter().zip(shape) {
+            pos += i * stride;
+            stride *= len;
+        }
+        pos as f32
+    }
+
+    #[test]
+    fn nd_index_reduce_and_permute() {
+        set_device(0);
+        let shape = [2u64, 3, 2, 2, 3];
+        let values: Vec<f32> = (0..72).map(|v| v as f32).collect();
+        let a = NdArray::new(&values, &shape).unwrap();
+
+        let b = a
+            .index(&[
+                NdIndex::All,
+                (1..3).into(),
+                1.into(),
+                NdIndex::All,
+                2.into(),
+            ])
+            .unwrap();
+        assert_eq!(b.shape(), &[2, 2, 1, 2, 1]);
+        let mut expected = Vec::new();
+        for l in 0..2 {
+            for j in 1..3 {
+                for i in 0..2 {
+                    expected.push(position(&[i, j, 1, l, 2], &shape));
+                }
+            }
+        }
+        assert_eq!(to_vec(&b), expected);
+
+        let s = a.sum(4).unwrap();
+        assert_eq!(s.shape(), &[2, 3, 2, 2, 1]);
+        assert_eq!(to_vec(&s)[0], 0.0 + 24.0 + 48.0);
+        assert_eq!(a.max(1).unwrap().shape(), &[2, 1, 2, 2, 3]);
+
+        let p = a.permute(&[4, 0, 1, 2, 3]).unwrap();
+        assert_eq!(p.shape(), &[3, 2, 3, 2, 2]);
+        assert_eq!(to_vec(&p)[..4], [0.0, 24.0, 48.0, 1.0]);
+
+        let q = a.permute(&[1, 0, 3, 4, 2]).unwrap();
+        assert_eq!(to_vec(&q)[..3], [0.0, 2.0, 4.0]);
+
+        let err = a.permute(&[4, 3, 2, 1, 0]).unwrap_err();
+        assert_eq!(err.code(), AfError::ERR_NOT_SUPPORTED);
+        let err = a
+            .index(&[0.into(), 0.into(), 0.into(), 0.into(), 0.into()])
+            .unwrap_err();
+        assert_eq!(err.code(), AfError::ERR_NOT_SUPPORTED);
+        let err = a.index(&[NdIndex::All, (2..4).into()]).unwrap_err();
+        assert_eq!(err.code(), AfError::ERR_SIZE);
+
+        let r = a.reshape(&[6, 12]).unwrap();
+        assert_eq!(r.array().dims(), crate::dim4!(6, 12));
+        assert!(a.reshape(&[5, 12]).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use super::core::{
-    af_array, af_features, dim_t, AfError, Array, HasAfEnum, HomographyType, ImageFilterType,
-    MatchType, RealFloating, HANDLE_ERROR,
+    af_array, af_features, dim_t, handle_wrapper_error, AfError, Array, Dim4, HasAfEnum,
+    HomographyType, ImageFilterType, MatchType, RealFloating, HANDLE_ERROR,
 };
 
 use libc::{c_float, c_int, c_uint};
@@ -200,6 +200,102 @@ impl Drop for Features {
     }
 }
 
+#[cfg(feature = "afserde")]
+mod afserde {
+    // Reimport required from super scope
+    use super::{af_array, AfError, Features, HANDLE_ERROR};
+
+    use libc::{c_int, c_uint, c_void};
+    use serde::de::{Deserializer, Error};
+    use serde::ser::Serializer;
+    use serde::{Deserialize, Serialize};
+
+    extern "C" {
+        fn af_write_array(arr: af_array, data: *const c_void, bytes: usize, src: c_uint) -> c_int;
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct FeaturesOnHost {
+        num_features: u64,
+        xpos: Vec<f32>,
+        ypos: Vec<f32>,
+        score: Vec<f32>,
+        orientation: Vec<f32>,
+        size: Vec<f32>,
+    }
+
+    fn to_vec(array: crate::core::Array<f32>) -> Vec<f32> {
+        let mut vec = vec![0.0; array.elements()];
+        array.host(&mut vec);
+        vec
+    }
+
+    /// Copy `values` into the memory of feature property `prop`
+    fn write_prop(prop: crate::core::Array<f32>, values: &[f32]) {
+        let err_val = unsafe {
+            af_write_array(
+                prop.get(),
+                values.as_ptr() as *const c_void,
+                std::mem::size_of_val(values),
+                1,
+            )
+        };
+        HANDLE_ERROR(AfError::from(err_val));
+    }
+
+    /// Serialize Implementation of Features
+    impl Serialize for Features {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let f = FeaturesOnHost {
+                num_features: self.num_features() as u64,
+                xpos: to_vec(self.xpos()),
+                ypos: to_vec(self.ypos()),
+                score: to_vec(self.score()),
+                orientation: to_vec(self.orientation()),
+                size: to_vec(self.size()),
+            };
+            f.serialize(serializer)
+        }
+    }
+
+    /// Deserialize Implementation of Features
+    impl<'de> Deserialize<'de> for Features {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let f = FeaturesOnHost::deserialize(deserializer)?;
+            let n = f.num_features as usize;
+            for (name, prop) in [
+                ("xpos", &f.xpos),
+                ("ypos", &f.ypos),
+                ("score", &f.score),
+                ("orientation", &f.orientation),
+                ("size", &f.size),
+            ] {
+                if prop.len() != n {
+                    return Err(Error::invalid_length(
+                        prop.len(),
+                        &format!("{} values of {}", n, name).as_str(),
+                    ));
+                }
+            }
+            let feat = Features::new(f.num_features);
+            if n > 0 {
+                write_prop(feat.xpos(), &f.xpos);
+                write_prop(feat.ypos(), &f.ypos);
+                write_prop(feat.score(), &f.score);
+                write_prop(feat.orientation(), &f.orientation);
+                write_prop(feat.size(), &f.size);
+            }
+            Ok(feat)
+        }
+    }
+}
+
 /// Fast feature detector
 ///
 /// A circle of radius 3 pixels, translating into a total of 16 pixels, is checked for sequential
@@ -495,10 +591,13 @@ where
     T: HasAfEnum + ImageFilterType,
     T::AbsOutType: HasAfEnum,
 {
-    match mtype {
-        MatchType::NCC | MatchType::ZNCC | MatchType::SHD => HANDLE_ERROR(AfError::ERR_ARG),
-        _ => (), // Do nothing valid matching type
-    };
+    if let MatchType::NCC | MatchType::ZNCC | MatchType::SHD = mtype {
+        handle_wrapper_error(
+            AfError::ERR_ARG,
+            &format!("match type {:?} is not supported", mtype),
+        );
+        return Array::new_empty(Dim4::new(&[0, 1, 1, 1]));
+    }
 
     let mut temp: af_array = std::ptr::null_mut();
     let err_val = unsafe {
@@ -673,6 +772,34 @@ where
 mod tests {
     use crate::randu;
 
+    #[cfg(feature = "afserde")]
+    mod serde_tests {
+        use super::super::Features;
+
+        #[test]
+        fn features_serde() {
+            crate::core::set_device(0);
+            let json = r#"{"num_features":2,"xpos":[1.0,2.0],"ypos":[3.0,4.0],
+                "score":[0.5,0.25],"orientation":[0.0,0.0],"size":[1.0,1.0]}"#;
+            let input: Features = serde_json::from_str(json).unwrap();
+
+            let encoded = bincode::serialize(&input).unwrap();
+            let decoded: Features = bincode::deserialize(&encoded).unwrap();
+
+            assert_eq!(decoded.num_features(), 2);
+            let mut xpos = vec![0.0f32; 2];
+            let mut score = vec![0.0f32; 2];
+            decoded.xpos().host(&mut xpos);
+            decoded.score().host(&mut score);
+            assert_eq!(xpos, vec![1.0, 2.0]);
+            assert_eq!(score, vec![0.5, 0.25]);
+
+            let invalid = r#"{"num_features":2,"xpos":[1.0],"ypos":[3.0,4.0],
+                "score":[0.5,0.25],"orientation":[0.0,0.0],"size":[1.0,1.0]}"#;
+            assert!(serde_json::from_str::<Features>(invalid).is_err());
+        }
+    }
+
     #[test]
     #[should_panic]
     fn check_invalid_matchtype() {
@@ -0,0 +1,32 @@
+use ::arrayfire::*;
+
+#[test]
+fn check_broadcast_arith() {
+    set_device(0);
+    let col = Array::new(&[1.0f32, 2.0, 3.0], dim4!(3, 1));
+    let row = Array::new(&[10.0f32, 20.0], dim4!(1, 2));
+
+    let sum = broadcast_add(&col, &row).unwrap();
+    assert_eq!(sum.dims(), dim4!(3, 2));
+    let mut res = vec![0.0f32; 6];
+    sum.host(&mut res);
+    assert_eq!(res, vec![11.0, 12.0, 13.0, 21.0, 22.0, 23.0]);
+
+    let matrix = constant(2.0f32, dim4!(3, 2));
+    let scaled = broadcast_mul(&matrix, &col).unwrap();
+    scaled.host(&mut res);
+    assert_eq!(res, vec![2.0, 4.0, 6.0, 2.0, 4.0, 6.0]);
+
+    let batched = constant(1.0f32, dim4!(3, 2, 4));
+    assert_eq!(
+        broadcast_sub(&batched, &row).unwrap().dims(),
+        dim4!(3, 2, 4)
+    );
+
+    let empty = Array::<f32>::new_empty(dim4!(0, 1));
+    let sum = broadcast_add(&empty, &constant(1.0f32, dim4!(1, 3))).unwrap();
+    assert_eq!(sum.dims(), dim4!(0, 3));
+
+    let err = broadcast_div(&matrix, &constant(1.0f32, dim4!(2, 3))).unwrap_err();
+    assert_eq!(err.code(), AfError::ERR_SIZE);
+}
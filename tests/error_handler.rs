@@ -1,3 +1,4 @@
+use std::sync::{Arc, Mutex};
 use std::thread;
 
 use ::arrayfire::*;
@@ -48,3 +49,70 @@ fn check_error_handler_mutation() {
         c.join();
     }
 }
+
+#[test]
+fn check_try_af_captures_error() {
+    let a = randu::<f32>(dim4!(3, 4));
+    let b = randu::<f32>(dim4!(5, 2));
+
+    let result = try_af(|| matmul(&a, &b, MatProp::NONE, MatProp::NONE));
+    let err = result.expect_err("matmul with mismatched dims must fail");
+    assert_eq!(err.code(), AfError::ERR_SIZE);
+
+    let nested = try_af(|| {
+        let inner = try_af(|| matmul(&a, &b, MatProp::NONE, MatProp::NONE));
+        assert!(inner.is_err());
+        a.elements()
+    });
+    assert_eq!(nested, Ok(12));
+}
+
+#[test]
+fn check_try_af_rejects_undersized_host_buffer() {
+    let a = randu::<f32>(dim4!(3, 3));
+    let mut too_small = vec![0.0f32; 4];
+
+    let err = try_af(|| a.host(&mut too_small)).expect_err("host with small buffer must fail");
+    assert_eq!(err.code(), AfError::ERR_SIZE);
+    assert!(err.message().contains("4 elements"));
+    assert_eq!(too_small, vec![0.0f32; 4]);
+}
+
+#[test]
+fn check_try_variants_return_errors() {
+    let a = randu::<f32>(dim4!(3, 4));
+    let b = randu::<f32>(dim4!(5, 2));
+
+    let err = try_add(&a, &b, false).expect_err("add with mismatched dims must fail");
+    assert_eq!(err.code(), AfError::ERR_SIZE);
+
+    let ones = constant(1.0f32, dim4!(3, 4));
+    let summed = try_sum(&ones, 0).expect("sum of valid Array must succeed");
+    assert_eq!(summed.dims(), dim4!(1, 4));
+    assert_eq!(try_sum_all(&ones), Ok((12.0, 0.0)));
+}
+
+#[test]
+fn check_scoped_error_handler() {
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let sink = errors.clone();
+    let recorder = Callback::new(move |error_code: AfError| {
+        if error_code != AfError::SUCCESS {
+            sink.lock().unwrap().push(error_code);
+        }
+    });
+
+    let a = randu::<f32>(dim4!(3, 4));
+    let b = randu::<f32>(dim4!(5, 2));
+
+    let elements = with_error_handler(recorder, || {
+        matmul(&a, &b, MatProp::NONE, MatProp::NONE);
+
+        // Innermost scope takes precedence
+        assert!(try_af(|| matmul(&a, &b, MatProp::NONE, MatProp::NONE)).is_err());
+        a.elements()
+    });
+
+    assert_eq!(elements, 12);
+    assert_eq!(*errors.lock().unwrap(), vec![AfError::ERR_SIZE]);
+}
@@ -0,0 +1,83 @@
+//! Installs a process wide memory manager, hence kept in its own test binary so that no
+//! other test allocates while it is active.
+
+use ::arrayfire::*;
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct Log {
+    allocated: usize,
+    in_use: usize,
+    frees: usize,
+}
+
+struct Capped {
+    limit: usize,
+    log: Arc<Mutex<Log>>,
+}
+
+impl MemoryManager for Capped {
+    fn alloc(
+        &mut self,
+        native: &NativeMemory,
+        _device: i32,
+        bytes: usize,
+    ) -> Result<*mut libc::c_void, AfError> {
+        let mut log = self.log.lock().unwrap();
+        if log.in_use + bytes > self.limit {
+            return Err(AfError::ERR_NO_MEM);
+        }
+        let ptr = native.alloc(bytes).map_err(AfError::from)?;
+        log.allocated += bytes;
+        log.in_use += bytes;
+        Ok(ptr)
+    }
+
+    unsafe fn free(
+        &mut self,
+        native: &NativeMemory,
+        _device: i32,
+        ptr: *mut libc::c_void,
+        bytes: usize,
+    ) {
+        let mut log = self.log.lock().unwrap();
+        log.in_use -= bytes;
+        log.frees += 1;
+        native.free(ptr).unwrap();
+    }
+}
+
+#[test]
+fn capped_memory_manager_on_cpu() {
+    set_backend(Backend::CPU);
+    set_device(0);
+    let log = Arc::new(Mutex::new(Log::default()));
+    set_memory_manager(Capped {
+        limit: 1 << 20,
+        log: log.clone(),
+    })
+    .unwrap();
+
+    {
+        let a = Array::new(&[1.0f32; 1024], dim4!(1024));
+        let b = &a + &a;
+        let mut host = vec![0.0f32; 1024];
+        b.host(&mut host);
+        assert_eq!(host[0], 2.0);
+        assert!(log.lock().unwrap().allocated >= 2 * 1024 * 4);
+    }
+    sync(0);
+    device_gc();
+    assert!(log.lock().unwrap().frees >= 2);
+
+    let too_large = try_af(|| Array::new(&vec![0u8; 2 << 20], dim4!(2 << 20)));
+    assert_eq!(too_large.unwrap_err().code(), AfError::ERR_NO_MEM);
+
+    // Buffers of live Arrays are not freed when the manager is shut down
+    let alive = Array::new(&[3.0f32; 256], dim4!(256));
+    unset_memory_manager().unwrap();
+    assert!(log.lock().unwrap().in_use >= 256 * 4);
+    let mut host = vec![0.0f32; 256];
+    alive.host(&mut host);
+    assert_eq!(host, vec![3.0f32; 256]);
+}